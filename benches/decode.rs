@@ -0,0 +1,192 @@
+//! 観測値の展開速度と、展開時のメモリ確保の回数を計測するベンチマーク
+//!
+//! `cargo bench --bench decode`で実行する。
+//! 計測に使用するRAPファイルは、メモリ上に生成する。
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+use time::macros::datetime;
+use time::PrimitiveDateTime;
+
+use jma::readers::RapReader;
+
+/// メモリ確保の回数を数えるアロケーター
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+/// 経度方向の格子数
+const NUMBER_OF_H_GRIDS: u16 = 1_000;
+
+/// 緯度方向の格子数
+const NUMBER_OF_V_GRIDS: u16 = 1_000;
+
+/// 観測データの数
+const NUMBER_OF_DATA: u8 = 24;
+
+/// 計測を繰り返す回数
+const ITERATIONS: usize = 5;
+
+fn main() {
+    let reader =
+        RapReader::from_bytes(build_rap_file()).expect("RAPファイルを読み込めませんでした。");
+    let dts = reader
+        .data_properties()
+        .iter()
+        .map(|dp| dp.observation_date_time)
+        .collect::<Vec<_>>();
+    let cells = reader.grid_cell_count() * dts.len();
+
+    let (elapsed, allocations) = measure(|| {
+        for dt in dts.iter() {
+            let grid = reader.to_grid(*dt).unwrap();
+            std::hint::black_box(&grid);
+        }
+    });
+    report("to_grid", cells, elapsed, allocations);
+
+    let mut buf = Vec::new();
+    let (elapsed, allocations) = measure(|| {
+        for dt in dts.iter() {
+            reader.decode_into(*dt, &mut buf).unwrap();
+            std::hint::black_box(&buf);
+        }
+    });
+    report("decode_into", cells, elapsed, allocations);
+}
+
+/// クロージャーを繰り返し実行して、1回あたりの経過時間とメモリ確保の回数を返す。
+fn measure<F>(mut f: F) -> (Duration, usize)
+where
+    F: FnMut(),
+{
+    // 1回目はバッファーの確保を含むため、計測しない
+    f();
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        f();
+    }
+    let elapsed = start.elapsed() / ITERATIONS as u32;
+    let allocations = (ALLOCATIONS.load(Ordering::Relaxed) - allocations) / ITERATIONS;
+
+    (elapsed, allocations)
+}
+
+fn report(name: &str, cells: usize, elapsed: Duration, allocations: usize) {
+    let throughput = cells as f64 / elapsed.as_secs_f64() / 1_000_000.0;
+    println!(
+        "{name:<12} {:>10.3} ms/iter {throughput:>10.1} Mcells/s {allocations:>6} allocs/iter",
+        elapsed.as_secs_f64() * 1_000.0,
+    );
+}
+
+/// 計測に使用するRAPファイルのバイト列を生成する。
+///
+/// 観測値は、同じレベルが続く格子と、レベルが1格子ごとに変わる格子を交互に配置する。
+fn build_rap_file() -> Vec<u8> {
+    let value_by_levels: [u16; 4] = [0, 10, 123, u16::MAX];
+    let blocks = (0..NUMBER_OF_DATA)
+        .map(|i| compress(i as usize))
+        .collect::<Vec<_>>();
+
+    let header_bytes =
+        80 + 4 + NUMBER_OF_DATA as usize * 20 + 40 + 4 + value_by_levels.len() * 2 + 2;
+    let mut bytes = Vec::new();
+
+    // コメント
+    bytes.extend_from_slice(b"JMA-RA01.00");
+    bytes.extend_from_slice(&[b' '; 66]);
+    bytes.extend_from_slice(&[0x0d, 0x0a, 0x00]);
+
+    // データ部へのインデックス
+    bytes.extend_from_slice(&(NUMBER_OF_DATA as u32).to_le_bytes());
+    let mut position = header_bytes;
+    for (i, block) in blocks.iter().enumerate() {
+        let dt = datetime!(2020-01-01 00:00) + time::Duration::hours(i as i64 + 1);
+        write_date_time(&mut bytes, dt);
+        bytes.extend_from_slice(&1u16.to_le_bytes());
+        bytes.extend_from_slice(&[0; 8]);
+        bytes.extend_from_slice(&(position as u32).to_le_bytes());
+        position += 4 + block.len() + 12;
+    }
+
+    // 格子系定義
+    bytes.extend_from_slice(&0u16.to_le_bytes());
+    bytes.extend_from_slice(&1u16.to_le_bytes());
+    bytes.extend_from_slice(&46_000_000u32.to_le_bytes());
+    bytes.extend_from_slice(&120_000_000u32.to_le_bytes());
+    bytes.extend_from_slice(&10_000u32.to_le_bytes());
+    bytes.extend_from_slice(&10_000u32.to_le_bytes());
+    bytes.extend_from_slice(&NUMBER_OF_H_GRIDS.to_le_bytes());
+    bytes.extend_from_slice(&NUMBER_OF_V_GRIDS.to_le_bytes());
+    bytes.extend_from_slice(&[0; 16]);
+
+    // 圧縮方法、観測値表、レベル反復数表
+    bytes.extend_from_slice(&1u16.to_le_bytes());
+    bytes.extend_from_slice(&(value_by_levels.len() as u16).to_le_bytes());
+    for value in value_by_levels {
+        bytes.extend_from_slice(&value.to_le_bytes());
+    }
+    bytes.extend_from_slice(&0u16.to_le_bytes());
+    assert_eq!(bytes.len(), header_bytes);
+
+    // データ部
+    for (i, block) in blocks.iter().enumerate() {
+        bytes.extend_from_slice(&(block.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(block);
+        bytes.extend_from_slice(&0u64.to_le_bytes());
+        bytes.extend_from_slice(&(i as u32).to_le_bytes());
+    }
+
+    bytes
+}
+
+/// 観測日時を書き込む。
+fn write_date_time(bytes: &mut Vec<u8>, dt: PrimitiveDateTime) {
+    bytes.extend_from_slice(&(dt.year() as u16).to_le_bytes());
+    bytes.extend_from_slice(&[dt.month() as u8, dt.day(), dt.hour(), dt.minute()]);
+}
+
+/// 観測値をランレングス圧縮する。
+///
+/// 100格子ごとに、レベル反復表によらないランレングス圧縮(b)と、頻度が多い単独のレベル値(c)を交互に使用する。
+fn compress(seed: usize) -> Vec<u8> {
+    let number_of_cells = NUMBER_OF_H_GRIDS as usize * NUMBER_OF_V_GRIDS as usize;
+    let mut compressed = Vec::new();
+    let mut cell = 0;
+    while cell < number_of_cells {
+        let level = ((cell / 100 + seed) % 4) as u8;
+        let count = 100.min(number_of_cells - cell);
+        if (cell / 100) % 2 == 0 {
+            compressed.push(0xC0 | level);
+            compressed.push((count - 2) as u8);
+        } else {
+            compressed.extend(std::iter::repeat_n(0x80 | level, count));
+        }
+        cell += count;
+    }
+
+    compressed
+}
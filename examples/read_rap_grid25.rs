@@ -5,7 +5,6 @@ use std::path::Path;
 
 use time::format_description::FormatItem;
 use time::macros::{datetime, format_description};
-use time::Duration;
 
 use jma::readers::{output_csv_with_geom, RapReader};
 
@@ -16,16 +15,16 @@ const FILE_DATETIME_FMT: &[FormatItem<'_>] =
 fn main() -> anyhow::Result<()> {
     let path = "resources/read_rap_grid25/J2001401.RAP";
     let reader = RapReader::new(path)?;
-    let grid_width = reader.grid_width() as f64 / 1e6;
-    let grid_height = reader.grid_height() as f64 / 1e6;
+    let grid_width = reader.grid_width_deg();
+    let grid_height = reader.grid_height_deg();
 
     reader.pretty_print(std::io::stdout().borrow_mut())?;
 
-    let mut dt = datetime!(2001-04-01 01:00);
+    let start_dt = datetime!(2001-04-01 01:00);
     let end_dt = datetime!(2001-04-02 00:00);
     let dest_dir_path = Path::new("resources/read_rap_grid25/dest");
-    while dt <= end_dt {
-        let iterator = reader.value_iterator(dt)?;
+    for (dt, iterator) in reader.value_iterators_between(start_dt, end_dt) {
+        let iterator = iterator?;
         let file_name = format!("{}.csv", dt.format(FILE_DATETIME_FMT).unwrap());
         let dest_file_path = dest_dir_path.join(file_name);
         let dest_file = OpenOptions::new()
@@ -35,7 +34,6 @@ fn main() -> anyhow::Result<()> {
             .open(dest_file_path)?;
         let mut writer = BufWriter::new(dest_file);
         output_csv_with_geom(&mut writer, iterator, grid_width, grid_height)?;
-        dt += Duration::hours(1);
     }
 
     Ok(())
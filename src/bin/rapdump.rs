@@ -0,0 +1,86 @@
+use std::fs::OpenOptions;
+use std::io::BufWriter;
+use std::path::PathBuf;
+
+use clap::Parser;
+use time::format_description::FormatItem;
+use time::macros::format_description;
+use time::PrimitiveDateTime;
+
+use jma::readers::{output_csv_with_geom, RapReader};
+
+/// コマンドライン引数で指定する日時の書式
+const ARG_DATETIME_FMT: &[FormatItem<'_>] =
+    format_description!("[year]-[month]-[day]T[hour]:[minute]");
+
+/// ファイル名に付与する日時の書式
+const FILE_DATETIME_FMT: &[FormatItem<'_>] =
+    format_description!("[year][month][day]T[hour][minute][second]");
+
+/// RAPファイルの情報を出力、または観測値をCSVファイルに出力する。
+#[derive(Parser)]
+#[command(name = "rapdump", version)]
+struct Args {
+    /// RAPファイルのパス
+    file: PathBuf,
+
+    /// ファイルの情報を出力する。
+    #[arg(long, conflicts_with = "csv")]
+    info: bool,
+
+    /// 観測値をジオメトリ付きCSVファイルに出力する。
+    #[arg(long)]
+    csv: bool,
+
+    /// CSVファイルに出力する観測値の日時（例: 2001-04-01T01:00）
+    #[arg(long, value_parser = parse_datetime, requires = "csv", conflicts_with = "all")]
+    datetime: Option<PrimitiveDateTime>,
+
+    /// 記録されているすべての日時の観測値をCSVファイルに出力する。
+    #[arg(long, requires = "csv")]
+    all: bool,
+
+    /// CSVファイルを出力するディレクトリ
+    #[arg(long, requires = "csv", default_value = ".")]
+    out: PathBuf,
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args = Args::parse();
+    let reader = RapReader::new(&args.file)?;
+
+    if !args.csv {
+        reader.pretty_print(&mut std::io::stdout())?;
+        return Ok(());
+    }
+
+    let dts = match (args.all, args.datetime) {
+        (true, _) => reader
+            .data_properties()
+            .iter()
+            .map(|dp| dp.observation_date_time)
+            .collect(),
+        (false, Some(dt)) => vec![dt],
+        (false, None) => return Err("`--datetime`または`--all`を指定してください。".into()),
+    };
+    let grid_width = reader.grid_width_deg();
+    let grid_height = reader.grid_height_deg();
+    for dt in dts {
+        let iterator = reader.value_iterator_shared(dt)?;
+        let file_name = format!("{}.csv", dt.format(FILE_DATETIME_FMT)?);
+        let dest_file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(args.out.join(file_name))?;
+        let mut writer = BufWriter::new(dest_file);
+        output_csv_with_geom(&mut writer, iterator, grid_width, grid_height)?;
+    }
+
+    Ok(())
+}
+
+/// コマンドライン引数の日時を解析する。
+fn parse_datetime(s: &str) -> Result<PrimitiveDateTime, time::error::Parse> {
+    PrimitiveDateTime::parse(s, ARG_DATETIME_FMT)
+}
@@ -1,2 +1,22 @@
 mod rap;
-pub use rap::{output_csv_with_geom, RapReader};
+#[cfg(feature = "gpkg")]
+pub use rap::output_geopackage;
+#[cfg(feature = "geo")]
+pub use rap::GeometryIterator;
+pub use rap::{
+    decode_grid, decode_run_length, diff_grids, find_grid, output_csv_points,
+    output_csv_points_with_options, output_csv_points_with_progress, output_csv_timeseries,
+    output_csv_timeseries_with_options, output_csv_with_geom, output_csv_with_geom_with_options,
+    output_csv_with_geom_with_progress, output_ndjson, parse_rap_filename, write_world_file,
+    Aggregation, BlockLayout, CompressionMethod, CoordinateAnchor, CsvWithGeomSink, DataProperty,
+    DecodeError, DecodedGrid, Decompressor, EnumerateGrid, ExportOptions, GridDefinition,
+    GridMetadata, GridSink, GridView, ImplausibleValuePolicy, IndexAnomaly, LevelHistogram,
+    LevelRepetition, LocationValue, MapType, ObservationElement, ObservationTimes, Origin,
+    Rainfall, RapDataReader, RapHeader, RapReader, RapReaderError, RapReaderOptions,
+    RapReaderResult, RapStreamReader, RapValueIterator, RemainderPolicy, ReservedRegion,
+    RunLengthBreakdown, RunLengthDecompressor, TileCoord,
+};
+#[cfg(feature = "netcdf")]
+pub use rap::{output_netcdf, output_netcdf_with_origin};
+#[cfg(feature = "parquet")]
+pub use rap::{output_parquet, output_parquet_with_options};
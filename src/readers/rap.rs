@@ -1,32 +1,248 @@
+use std::borrow::Cow;
 use std::fs::{File, OpenOptions};
-use std::io::{BufReader, Read, Seek, SeekFrom, Write};
-use std::path::{Path, PathBuf};
+use std::io::{BufReader, Cursor, Read, Seek, SeekFrom, Write};
+use std::path::Path;
+use std::sync::{Arc, Mutex, OnceLock};
 
 use time::format_description::FormatItem;
-use time::macros::format_description;
-use time::{Date, Month, PrimitiveDateTime, Time};
+use time::macros::{format_description, offset};
+use time::{Date, Duration, Month, OffsetDateTime, PrimitiveDateTime, Time, UtcOffset};
+
+mod aggregate;
+pub use aggregate::{Aggregation, RemainderPolicy};
+mod decode;
+pub use decode::{
+    decode_grid, decode_run_length, DecodeError, Decompressor, RunLengthDecompressor,
+};
+use decode::{decode_grid_into, ExpandedValue};
+mod decoded_grid;
+pub use decoded_grid::DecodedGrid;
+mod directory;
+pub use directory::find_grid;
+mod filename;
+pub use filename::parse_rap_filename;
+mod grid_view;
+pub use grid_view::GridView;
+mod header;
+pub use header::RapHeader;
+mod index_anomaly;
+pub use index_anomaly::IndexAnomaly;
+#[cfg(feature = "geo")]
+mod geo;
+#[cfg(feature = "geo")]
+pub use geo::GeometryIterator;
+#[cfg(feature = "gpkg")]
+mod gpkg;
+#[cfg(feature = "gpkg")]
+pub use gpkg::output_geopackage;
+#[cfg(feature = "gzip")]
+mod gzip;
+#[cfg(feature = "netcdf")]
+mod netcdf;
+#[cfg(feature = "netcdf")]
+pub use netcdf::{output_netcdf, output_netcdf_with_origin};
+mod ndjson;
+pub use ndjson::output_ndjson;
+#[cfg(feature = "parquet")]
+mod parquet;
+#[cfg(feature = "parquet")]
+pub use parquet::{output_parquet, output_parquet_with_options};
+mod rainfall;
+pub use rainfall::Rainfall;
+mod sink;
+use sink::drive_sink;
+pub use sink::{CsvWithGeomSink, GridMetadata, GridSink};
+mod statistics;
+pub use statistics::{diff_grids, LevelHistogram, RunLengthBreakdown};
+mod stream;
+pub use stream::RapStreamReader;
+#[cfg(test)]
+mod tests;
+mod tile;
+pub use tile::TileCoord;
+#[cfg(feature = "tokio")]
+mod tokio;
+mod writer;
 
 type FileReader = BufReader<File>;
 
+/// メモリ上のRAPファイルのリーダー
+type BytesReader = Cursor<Arc<[u8]>>;
+
+/// 日本標準時のオフセット
+const JST_OFFSET: UtcOffset = offset!(+9);
+
+/// 観測値の1単位あたりの雨量（mm）
+///
+/// 観測値は0.1mm単位で記録されている。
+const MM_PER_VALUE: f64 = 0.1;
+
+/// 格子の中心の緯度の最大値（10e-6度単位）
+const MAX_LATITUDE: u64 = 90_000_000;
+
+/// 格子の中心の経度の最大値（10e-6度単位）
+const MAX_LONGITUDE: u64 = 180_000_000;
+
+/// 引数で指定された座標を含む格子の、記録順のインデックスを返す。
+///
+/// # 引数
+///
+/// * `bounds` - 観測範囲（`(最小経度, 最小緯度, 最大経度, 最大緯度)`）
+/// * `cell_size` - 格子の幅と高さ（度）
+/// * `dimensions` - 経度方向と緯度方向の格子数
+/// * `longitude` - 経度（度）
+/// * `latitude` - 緯度（度）
+///
+/// # 戻り値
+///
+/// 格子のインデックス、座標が観測範囲外の場合は`None`
+fn locate_cell(
+    (min_lon, _, _, max_lat): (f64, f64, f64, f64),
+    (width, height): (f64, f64),
+    (number_of_h_grids, number_of_v_grids): (u16, u16),
+    longitude: f64,
+    latitude: f64,
+) -> Option<usize> {
+    let col = ((longitude - min_lon) / width).floor();
+    let row = ((max_lat - latitude) / height).floor();
+    if !(0.0..number_of_h_grids as f64).contains(&col)
+        || !(0.0..number_of_v_grids as f64).contains(&row)
+    {
+        return None;
+    }
+
+    Some(row as usize * number_of_h_grids as usize + col as usize)
+}
+
+/// 格子の大きさを概算するときの地球の半径（メートル）
+const EARTH_RADIUS_METERS: f64 = 6_371_000.0;
+
+/// 10e-6度単位の緯度、経度または長さを度単位に変換する。
+fn to_degrees(value: u32) -> f64 {
+    value as f64 / 1_000_000.0
+}
+
+/// CSVファイルに出力する緯度と経度の既定の小数点以下の桁数
+const DEFAULT_PRECISION: usize = 6;
+
+/// データ部 - 圧縮後の大きさのバイト数
+const COMPRESSED_DATA_SIZE_BYTES: u64 = 4;
+
+/// RAPファイルを読み込むバッファーの既定のバイト数
+const DEFAULT_BUFFER_CAPACITY: usize = 8 * 1024;
+
+/// `RapReader`の概要に表示する日時の書式
+const SUMMARY_DATETIME_FMT: &[FormatItem<'_>] =
+    format_description!("[year]-[month]-[day] [hour]:[minute]");
+
 /// 日時の書式
 const DATETIME_FMT: &[FormatItem<'_>] =
     format_description!("[year]-[month]-[day] [hour]:[minute]:[second]");
 
 /// `RapReader`
+///
+/// 複製した`RapReader`は、RAPファイルのパスまたはメモリ上のバイト列を複製元と共有する。
+/// `value_iterator_shared`メソッドで再利用するRAPファイル・リーダーは共有しない。
 #[derive(Debug)]
 pub struct RapReader {
-    /// パス
-    path: PathBuf,
+    /// RAPファイルの読み込み元
+    source: RapSource,
     /// コメント
     comment_part: CommentPart,
     /// データ部へのインデックス
     data_index_part: DataIndexPart,
     /// 格子系定義
-    grid_definition_part: GridDefinitionPart,
+    grid_definition: GridDefinition,
     /// 圧縮方法、観測値表
     compression_part: CompressionPart,
     /// レベル反復数表
     level_repetitions_part: LevelRepetitionsPart,
+    /// `value_iterator_shared`メソッドで再利用するRAPファイル・リーダー
+    shared_reader: Mutex<Option<RapDataReader>>,
+    /// `open_lazy`メソッドで開いた場合に、データ部から読み込んだ観測データの属性
+    ///
+    /// データ部へのインデックスと同じ順に格納する。
+    /// すべての観測データの属性を開くときに読み込んだ場合は`None`である。
+    loaded_data_properties: Option<Box<[OnceLock<DataProperty>]>>,
+    /// 管理部の予備の領域
+    ///
+    /// 予備の領域を保持しない場合は`None`である。
+    reserved_regions: Option<Vec<ReservedRegion>>,
+}
+
+impl Clone for RapReader {
+    fn clone(&self) -> Self {
+        Self {
+            source: self.source.clone(),
+            comment_part: self.comment_part.clone(),
+            data_index_part: self.data_index_part.clone(),
+            grid_definition: self.grid_definition,
+            compression_part: self.compression_part.clone(),
+            level_repetitions_part: self.level_repetitions_part.clone(),
+            shared_reader: Mutex::new(None),
+            loaded_data_properties: self.loaded_data_properties.clone(),
+            reserved_regions: self.reserved_regions.clone(),
+        }
+    }
+}
+
+/// RAPファイルを読み込むときのオプション
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RapReaderOptions {
+    /// コメントの末尾3バイトが`0x0d 0x0a 0x00`であることを厳密に検証するかを示すフラグ
+    ///
+    /// `false`の場合、コメントの末尾3バイトが異なっていても無視して読み込みを続ける。
+    /// 破損したファイルを見逃さないように、既定値は`true`である。
+    pub strict_comment_trailer: bool,
+
+    /// RAPファイルを読み込むバッファーのバイト数
+    ///
+    /// ネットワーク・ファイルシステム上のファイルなど、読み込みの回数を減らしたい場合に大きくする。
+    /// 管理部を読み込むときと、観測値を読み込むときに使用する。
+    /// 既定値は`BufReader`の既定値と同じ8KiBである。
+    pub buffer_capacity: usize,
+
+    /// 管理部の予備の領域に記録されているバイト列を保持するかを示すフラグ
+    ///
+    /// `true`の場合、データ部へのインデックスと格子系定義の予備の領域を読み込んで保持し、
+    /// `reserved_regions`メソッドで取得できるようにする。
+    /// 仕様と異なるRAPファイルを調査するときに使用する。既定値は`false`である。
+    pub capture_reserved_regions: bool,
+
+    /// 観測値として妥当な最大値
+    ///
+    /// 指定した場合、観測値表に最大値を超える観測値が記録されているレベルを、
+    /// `implausible_value_policy`に従って欠測値として扱うか、エラーとする。
+    /// 欠測値として扱う場合は、観測値表の観測値を欠測値に置き換えるため、
+    /// `RapReader::value_by_levels`も置き換えた観測値を返す。
+    /// 破損した観測値表から、あり得ない雨量を展開することを防ぐときに使用する。
+    /// 極端な観測値を意図せず変更しないように、既定値は`None`（確認しない）である。
+    pub max_plausible_value: Option<u16>,
+
+    /// 観測値が`max_plausible_value`を超えた場合の扱い
+    pub implausible_value_policy: ImplausibleValuePolicy,
+}
+
+impl Default for RapReaderOptions {
+    fn default() -> Self {
+        Self {
+            strict_comment_trailer: true,
+            buffer_capacity: DEFAULT_BUFFER_CAPACITY,
+            capture_reserved_regions: false,
+            max_plausible_value: None,
+            implausible_value_policy: ImplausibleValuePolicy::default(),
+        }
+    }
+}
+
+/// 観測値が`RapReaderOptions::max_plausible_value`を超えた場合の扱い
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum ImplausibleValuePolicy {
+    /// 最大値を超えた観測値を、欠測値として扱う
+    #[default]
+    Missing,
+    /// RAPファイルを読み込むときに、`RapReaderError::ImplausibleValue`を返す
+    Error,
 }
 
 impl RapReader {
@@ -40,28 +256,246 @@ impl RapReader {
     ///
     /// `RapReader`
     pub fn new<P>(path: P) -> RapReaderResult<Self>
+    where
+        P: AsRef<Path>,
+    {
+        Self::with_options(path, RapReaderOptions::default())
+    }
+
+    /// オプションを指定してRAPファイルを開く
+    ///
+    /// `gzip`フィーチャーが有効な場合、拡張子が`gz`のファイル、またはgzipで圧縮されたファイルは、
+    /// ファイル全体をメモリ上に展開してから読み込む。
+    /// 観測値を読み込むときにデータ部の位置へシークする必要があるためで、
+    /// 展開後のファイルの大きさと同じだけメモリを使用する。
+    ///
+    /// # 引数
+    ///
+    /// * `path` - 開くRAPファイルのパス
+    /// * `options` - RAPファイルを読み込むときのオプション
+    ///
+    /// # 戻り値
+    ///
+    /// `RapReader`
+    pub fn with_options<P>(path: P, options: RapReaderOptions) -> RapReaderResult<Self>
     where
         P: AsRef<Path>,
     {
         let path = Path::new(path.as_ref()).to_path_buf();
+        #[cfg(feature = "gzip")]
+        if gzip::is_gzip(&path)? {
+            return Self::from_gzip(&path, &options, false);
+        }
+        let file = OpenOptions::new()
+            .read(true)
+            .open(&path)
+            .map_err(|e| RapReaderError::Open(format!("{e}")))?;
+        let mut reader = BufReader::with_capacity(options.buffer_capacity, file);
+
+        Self::from_reader(
+            RapSource::File {
+                path: Arc::from(path),
+                buffer_capacity: options.buffer_capacity,
+            },
+            &mut reader,
+            &options,
+            false,
+        )
+    }
+
+    /// RAPファイルを読み込むバッファーのバイト数を指定してRAPファイルを開く。
+    ///
+    /// 管理部を読み込むときと、観測値を読み込むときに、指定したバイト数のバッファーを使用する。
+    ///
+    /// # 引数
+    ///
+    /// * `path` - 開くRAPファイルのパス
+    /// * `capacity` - RAPファイルを読み込むバッファーのバイト数
+    ///
+    /// # 戻り値
+    ///
+    /// `RapReader`
+    pub fn with_buffer_capacity<P>(path: P, capacity: usize) -> RapReaderResult<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let options = RapReaderOptions {
+            buffer_capacity: capacity,
+            ..Default::default()
+        };
+
+        Self::with_options(path, options)
+    }
+
+    /// データ部を読み込まずにRAPファイルを開く。
+    ///
+    /// `new`メソッドは、開くときにすべてのデータ部へシークして、圧縮後の大きさ、レーダー運用状況及び
+    /// 解析に使用したアメダスの総数を読み込む。
+    /// このメソッドは管理部のみを読み込み、データ部は観測データを取得するときに読み込んで保持する。
+    /// 多くの日の観測データを連結した大きなファイルを、短時間で開くときに使用する。
+    ///
+    /// `data_properties`メソッドが返す観測データの属性には、データ部から読み込む値が記録されていない。
+    /// データ部から読み込む値は、`data_property`メソッドで取得する。
+    ///
+    /// # 引数
+    ///
+    /// * `path` - 開くRAPファイルのパス
+    ///
+    /// # 戻り値
+    ///
+    /// `RapReader`
+    pub fn open_lazy<P>(path: P) -> RapReaderResult<Self>
+    where
+        P: AsRef<Path>,
+    {
+        Self::open_lazy_with_options(path, RapReaderOptions::default())
+    }
+
+    /// オプションを指定して、データ部を読み込まずにRAPファイルを開く。
+    ///
+    /// `gzip`フィーチャーが有効な場合、gzipで圧縮されたファイルは途中から展開できないため、
+    /// `with_options`メソッドと同様にファイル全体をメモリ上に展開する。
+    /// 展開したバイト列からも管理部のみを読み込み、データ部は観測データを取得するときに読み込む。
+    ///
+    /// # 引数
+    ///
+    /// * `path` - 開くRAPファイルのパス
+    /// * `options` - RAPファイルを読み込むときのオプション
+    ///
+    /// # 戻り値
+    ///
+    /// `RapReader`
+    pub fn open_lazy_with_options<P>(path: P, options: RapReaderOptions) -> RapReaderResult<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let path = Path::new(path.as_ref()).to_path_buf();
+        #[cfg(feature = "gzip")]
+        if gzip::is_gzip(&path)? {
+            return Self::from_gzip(&path, &options, true);
+        }
         let file = OpenOptions::new()
             .read(true)
             .open(&path)
             .map_err(|e| RapReaderError::Open(format!("{e}")))?;
-        let mut reader = BufReader::new(file);
-        let comment_part = read_comment_part(&mut reader)?;
-        let data_index_part = read_data_index_part(&mut reader)?;
-        let grid_definition_part = read_grid_definition_part(&mut reader)?;
-        let compression_part = read_compression_part(&mut reader)?;
-        let level_repetitions_part = read_level_repetitions_part(&mut reader)?;
+        let mut reader = BufReader::with_capacity(options.buffer_capacity, file);
+
+        Self::from_reader(
+            RapSource::File {
+                path: Arc::from(path),
+                buffer_capacity: options.buffer_capacity,
+            },
+            &mut reader,
+            &options,
+            true,
+        )
+    }
+
+    /// メモリ上のRAPファイルのバイト列を読み込む。
+    ///
+    /// 引数のバイト列は`RapReader`が保持して、観測値を読み込むときに使用する。
+    ///
+    /// # 引数
+    ///
+    /// * `bytes` - RAPファイルのバイト列
+    ///
+    /// # 戻り値
+    ///
+    /// `RapReader`
+    pub fn from_bytes<B>(bytes: B) -> RapReaderResult<Self>
+    where
+        B: Into<Arc<[u8]>>,
+    {
+        Self::from_bytes_with_options(bytes, RapReaderOptions::default())
+    }
+
+    /// オプションを指定して、メモリ上のRAPファイルのバイト列を読み込む。
+    ///
+    /// # 引数
+    ///
+    /// * `bytes` - RAPファイルのバイト列
+    /// * `options` - RAPファイルを読み込むときのオプション
+    ///
+    /// # 戻り値
+    ///
+    /// `RapReader`
+    pub fn from_bytes_with_options<B>(bytes: B, options: RapReaderOptions) -> RapReaderResult<Self>
+    where
+        B: Into<Arc<[u8]>>,
+    {
+        let bytes = bytes.into();
+        let mut reader = Cursor::new(Arc::clone(&bytes));
+
+        Self::from_reader(
+            RapSource::Bytes { bytes, path: None },
+            &mut reader,
+            &options,
+            false,
+        )
+    }
+
+    /// gzipで圧縮されたRAPファイルをメモリ上に展開して読み込む。
+    ///
+    /// 展開したバイト列と一緒に元のファイルのパスを保持して、ファイル名の照合などに使用する。
+    #[cfg(feature = "gzip")]
+    fn from_gzip(path: &Path, options: &RapReaderOptions, lazy: bool) -> RapReaderResult<Self> {
+        let bytes: Arc<[u8]> = gzip::decompress(path)?.into();
+        let mut reader = Cursor::new(Arc::clone(&bytes));
+
+        Self::from_reader(
+            RapSource::Bytes {
+                bytes,
+                path: Some(Arc::from(path)),
+            },
+            &mut reader,
+            options,
+            lazy,
+        )
+    }
+
+    /// リーダーから管理部を読み込む。
+    ///
+    /// `lazy`が`true`の場合は、データ部を読み込まない。
+    fn from_reader<R>(
+        source: RapSource,
+        reader: &mut R,
+        options: &RapReaderOptions,
+        lazy: bool,
+    ) -> RapReaderResult<Self>
+    where
+        R: Read + Seek,
+    {
+        let RapHeader {
+            comment_part,
+            mut data_index_part,
+            grid_definition,
+            compression_part,
+            level_repetitions_part,
+            reserved_regions,
+        } = RapHeader::parse_with_options(reader, options)?;
+
+        // データ部に移動してデータ部に記録されている情報を取得
+        let loaded_data_properties = if lazy {
+            let number_of_data = data_index_part.data_properties.len();
+            Some((0..number_of_data).map(|_| OnceLock::new()).collect())
+        } else {
+            for data_property in data_index_part.data_properties.iter_mut() {
+                reader.seek(SeekFrom::Start(data_property.data_start_position as u64))?;
+                read_data_block_metadata(reader, data_property)?;
+            }
+            None
+        };
 
         Ok(Self {
-            path,
+            source,
             comment_part,
             data_index_part,
-            grid_definition_part,
+            grid_definition,
             compression_part,
             level_repetitions_part,
+            shared_reader: Mutex::new(None),
+            loaded_data_properties,
+            reserved_regions,
         })
     }
 
@@ -75,6 +509,27 @@ impl RapReader {
         &self.comment_part.version
     }
 
+    /// 管理部 - コメント - 版番号を、`(メジャー・バージョン, マイナー・バージョン)`で返す。
+    ///
+    /// 版番号は5バイトの文字列で、`01.00`のように`.`で区切ったメジャー・バージョンと
+    /// マイナー・バージョンを記録している。前後の空白は無視する。
+    /// 表示には`version`メソッドを使用する。
+    ///
+    /// # 戻り値
+    ///
+    /// `(メジャー・バージョン, マイナー・バージョン)`、版番号を解析できない場合は`None`
+    pub fn format_version(&self) -> Option<(u16, u16)> {
+        let (major, minor) = self.comment_part.version.trim().split_once('.')?;
+        let parse = |s: &str| {
+            s.bytes()
+                .all(|b| b.is_ascii_digit())
+                .then(|| s.parse::<u16>().ok())
+                .flatten()
+        };
+
+        Some((parse(major)?, parse(minor)?))
+    }
+
     /// 管理部 - コメント - 作成者コメントを返す。
     pub fn creator_comment(&self) -> &str {
         &self.comment_part.creator_comment
@@ -85,49 +540,457 @@ impl RapReader {
         self.data_index_part.number_of_data as u32
     }
 
+    /// 管理部 - データ部へのインデックス - 1日の観測回数を返す。
+    pub fn observation_times(&self) -> ObservationTimes {
+        self.data_index_part.number_of_data
+    }
+
     /// 記録しているデータの属性を格納したスライスを返す。
     ///
     /// RAPファイルは、1つのファイルに1日分のデータを記録している。
     /// 1つのファイルには、1時間間隔で観測した24データ、または30分間隔で観測した48データが
     /// 記録されている。
     /// データ数は、`number_of_data`メソッドで確認できる。
+    ///
+    /// `open_lazy`メソッドで開いた場合、圧縮後の大きさ、レーダー運用状況及び解析に使用したアメダスの総数は
+    /// 記録されていないため、`data_property`メソッドで取得する。
     pub fn data_properties(&self) -> &[DataProperty] {
         &self.data_index_part.data_properties
     }
 
+    /// 管理部の予備の領域に記録されているバイト列を、ファイル内の位置の順に返す。
+    ///
+    /// `RapReaderOptions::capture_reserved_regions`を`true`にして開いた場合のみ、予備の領域を返す。
+    ///
+    /// # 戻り値
+    ///
+    /// 管理部の予備の領域、予備の領域を保持していない場合は`None`
+    pub fn reserved_regions(&self) -> Option<&[ReservedRegion]> {
+        self.reserved_regions.as_deref()
+    }
+
+    /// 引数で指定された日時の観測データが記録されているか確認する。
+    ///
+    /// # 引数
+    ///
+    /// * `dt` - 確認する日時
+    ///
+    /// # 戻り値
+    ///
+    /// 観測データが記録されている場合は`true`、記録されていない場合は`false`
+    pub fn contains(&self, dt: PrimitiveDateTime) -> bool {
+        self.data_properties()
+            .iter()
+            .any(|dp| dp.observation_date_time == dt)
+    }
+
+    /// 引数で指定された日時の観測データの属性を返す。
+    ///
+    /// `open_lazy`メソッドで開いた場合は、最初に呼び出されたときにデータ部を読み込んで保持する。
+    ///
+    /// # 引数
+    ///
+    /// * `dt` - 観測データの属性を取得する日時
+    ///
+    /// # 戻り値
+    ///
+    /// 観測データの属性、観測データが記録されていない場合またはデータ部を読み込めなかった場合は`None`
+    pub fn data_property(&self, dt: PrimitiveDateTime) -> Option<&DataProperty> {
+        self.resolve_data_property(dt).ok()
+    }
+
+    /// 引数で指定されたインデックスの観測データの属性を返す。
+    ///
+    /// インデックスは、データ部へのインデックスに記録されている順番である。
+    /// `open_lazy`メソッドで開いた場合は、最初に呼び出されたときにデータ部を読み込んで保持する。
+    ///
+    /// # 引数
+    ///
+    /// * `index` - 観測データの属性を取得するインデックス
+    ///
+    /// # 戻り値
+    ///
+    /// 観測データの属性、インデックスが範囲外の場合またはデータ部を読み込めなかった場合は`None`
+    pub fn data_property_by_index(&self, index: usize) -> Option<&DataProperty> {
+        self.resolve_data_property_at(index).ok()
+    }
+
+    /// 引数で指定された日時の観測データの属性を、必要であればデータ部を読み込んで返す。
+    fn resolve_data_property(&self, dt: PrimitiveDateTime) -> RapReaderResult<&DataProperty> {
+        let index = self
+            .data_properties()
+            .iter()
+            .position(|dp| dp.observation_date_time == dt)
+            .ok_or(RapReaderError::DataDoesNotRecorded(dt))?;
+
+        self.resolve_data_property_at(index)
+    }
+
+    /// 引数で指定されたインデックスの観測データの属性を、必要であればデータ部を読み込んで返す。
+    fn resolve_data_property_at(&self, index: usize) -> RapReaderResult<&DataProperty> {
+        let dp = self
+            .data_properties()
+            .get(index)
+            .ok_or(RapReaderError::DataIndexOutOfRange {
+                index,
+                number_of_data: self.data_properties().len(),
+            })?;
+        let Some(loaded_data_properties) = &self.loaded_data_properties else {
+            return Ok(dp);
+        };
+        if let Some(loaded) = loaded_data_properties[index].get() {
+            return Ok(loaded);
+        }
+
+        let mut data_property = *dp;
+        let mut reader = self.source.open()?;
+        reader.seek(SeekFrom::Start(data_property.data_start_position as u64))?;
+        read_data_block_metadata(&mut reader, &mut data_property)?;
+
+        Ok(loaded_data_properties[index].get_or_init(|| data_property))
+    }
+
+    /// データ部から読み込んだ値を含めた、すべての観測データの属性を返す。
+    ///
+    /// データ部を読み込めなかった観測データは、データ部へのインデックスに記録されている属性を返す。
+    fn resolved_data_properties(&self) -> Vec<DataProperty> {
+        self.data_properties()
+            .iter()
+            .enumerate()
+            .map(|(index, dp)| *self.resolve_data_property_at(index).unwrap_or(dp))
+            .collect()
+    }
+
+    /// 引数で指定された日時の解析に使用したアメダスの総数を返す。
+    ///
+    /// 観測値を展開せずに、管理部を読み込んだときに取得したアメダスの総数を返す。
+    ///
+    /// # 引数
+    ///
+    /// * `dt` - アメダスの総数を取得する日時
+    ///
+    /// # 戻り値
+    ///
+    /// 解析に使用したアメダスの総数、観測データが記録されていない場合は`None`
+    pub fn amedas_count(&self, dt: PrimitiveDateTime) -> Option<u32> {
+        self.data_property(dt).map(|dp| dp.number_of_amedas)
+    }
+
+    /// 引数で指定された日時のレーダー運用状況を返す。
+    ///
+    /// 観測値を展開せずに、管理部を読み込んだときに取得したレーダー運用状況を返す。
+    ///
+    /// # 引数
+    ///
+    /// * `dt` - レーダー運用状況を取得する日時
+    ///
+    /// # 戻り値
+    ///
+    /// レーダー運用状況、観測データが記録されていない場合は`None`
+    pub fn radar_operation_statuses(&self, dt: PrimitiveDateTime) -> Option<u64> {
+        self.data_property(dt).map(|dp| dp.radar_operation_statuses)
+    }
+
+    /// 観測データごとのデータ部の配置を返す。
+    ///
+    /// 観測値を展開せずに、データ部のバイト位置と大きさを取得できる。
+    pub fn block_layout(&self) -> Vec<BlockLayout> {
+        self.resolved_data_properties()
+            .iter()
+            .map(|dp| BlockLayout {
+                datetime: dp.observation_date_time,
+                data_start_position: dp.data_start_position,
+                compressed_data_size: dp.compressed_data_size,
+                radar_operation_statuses: dp.radar_operation_statuses,
+                number_of_amedas: dp.number_of_amedas,
+            })
+            .collect()
+    }
+
+    /// データ部の構造を検証する。
+    ///
+    /// 観測データごとに、データ部に記録されている圧縮後の大きさが管理部を読み込んだときと一致すること、
+    /// 圧縮データ、レーダー運用状況及びアメダスの総数がファイルの範囲内に収まること、
+    /// 次のデータ部と重なっていないことを確認する。
+    /// 圧縮データは展開しないため、ファイル全体を展開するより高速に、途中で途切れたファイルを検出できる。
+    ///
+    /// # 戻り値
+    ///
+    /// データ部の構造が正しい場合は`()`
+    pub fn verify(&self) -> RapReaderResult<()> {
+        let mut reader = self.source.open()?;
+        let file_size = reader.seek(SeekFrom::End(0))?;
+
+        let mut data_properties = (0..self.data_properties().len())
+            .map(|index| self.resolve_data_property_at(index))
+            .collect::<RapReaderResult<Vec<_>>>()?;
+        data_properties.sort_by_key(|dp| dp.data_start_position);
+        for (index, dp) in data_properties.iter().enumerate() {
+            let invalid_block = |reason: String| RapReaderError::InvalidBlock {
+                datetime: dp.observation_date_time,
+                reason,
+            };
+            // 圧縮後の大きさ、圧縮データ、レーダー運用状況(8バイト)及びアメダスの総数(4バイト)
+            let end_position = dp.compressed_data_end_position() + 8 + 4;
+            if file_size < end_position {
+                return Err(invalid_block(format!(
+                    "データ部の末尾(0x{end_position:X})がファイルの大きさ(0x{file_size:X})を超えています。"
+                )));
+            }
+            reader.seek(SeekFrom::Start(dp.data_start_position as u64))?;
+            let compressed_data_size = read_u32(&mut reader, "データ部の圧縮後の大きさ")?;
+            if compressed_data_size != dp.compressed_data_size {
+                return Err(invalid_block(format!(
+                    "データ部の圧縮後の大きさ({compressed_data_size})が、管理部を読み込んだときの大きさ({})と一致しません。",
+                    dp.compressed_data_size
+                )));
+            }
+            if let Some(next) = data_properties.get(index + 1) {
+                if (next.data_start_position as u64) < end_position {
+                    return Err(invalid_block(format!(
+                        "データ部の末尾(0x{end_position:X})が、次のデータ部の先頭(0x{:X})と重なっています。",
+                        next.data_start_position
+                    )));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// 記録されている観測データの最も古い日時と最も新しい日時を返す。
+    ///
+    /// # 戻り値
+    ///
+    /// `(最も古い日時, 最も新しい日時)`、観測データが記録されていない場合は`None`
+    pub fn observation_range(&self) -> Option<(PrimitiveDateTime, PrimitiveDateTime)> {
+        let mut dts = self
+            .data_properties()
+            .iter()
+            .map(|dp| dp.observation_date_time);
+        let first = dts.next()?;
+
+        Some(dts.fold((first, first), |(min, max), dt| (min.min(dt), max.max(dt))))
+    }
+
+    /// 観測値を1時間あたりの雨量（mm/h）に換算する。
+    ///
+    /// 観測値は、観測日時までの観測間隔に積算した雨量である。
+    /// 30分間隔で観測したファイル（48回/日）の観測値は30分間の積算雨量であるため、2倍して1時間あたりに換算する。
+    /// 1時間間隔で観測したファイル（24回/日）の観測値は、そのまま1時間あたりの雨量となる。
+    ///
+    /// # 引数
+    ///
+    /// * `raw` - 観測値
+    ///
+    /// # 戻り値
+    ///
+    /// 1時間あたりの雨量（mm/h）
+    pub fn value_as_rate_mm_per_hour(&self, raw: u16) -> f64 {
+        let interval = self.observation_times().interval();
+        let factor = Duration::hours(1) / interval;
+
+        raw as f64 * MM_PER_VALUE * factor
+    }
+
+    /// 管理部 - 格子系定義を返す。
+    pub fn grid_definition(&self) -> GridDefinition {
+        self.grid_definition
+    }
+
+    /// 引数で指定されたRAPファイル・リーダーと、格子系定義が一致するか確認する。
+    ///
+    /// 地図種別、最北西端の格子の中心の座標、格子の幅と高さ及び格子数を比較する。
+    /// 2つのRAPファイルの観測値を格子ごとに積算または比較する前に使用する。
+    ///
+    /// # 引数
+    ///
+    /// * `other` - 格子系定義を比較するRAPファイル・リーダー
+    ///
+    /// # 戻り値
+    ///
+    /// 格子系定義が一致する場合は`true`
+    pub fn grid_definition_matches(&self, other: &RapReader) -> bool {
+        self.grid_definition == other.grid_definition
+    }
+
+    /// 引数で指定されたRAPファイル・リーダーと格子系定義が一致しない場合に、
+    /// `RapReaderError::GridDefinitionMismatch`を返す。
+    fn ensure_grid_definition_matches(&self, other: &RapReader) -> RapReaderResult<()> {
+        if !self.grid_definition_matches(other) {
+            return Err(RapReaderError::GridDefinitionMismatch(
+                self.grid_definition,
+                other.grid_definition,
+            ));
+        }
+
+        Ok(())
+    }
+
     /// 管理部 - 格子系定義 - 地図種別を返す。
     pub fn map_type(&self) -> u16 {
-        self.grid_definition_part.map_type
+        self.grid_definition.map_type
+    }
+
+    /// 管理部 - 格子系定義 - 地図種別を`MapType`で返す。
+    pub fn map_type_kind(&self) -> MapType {
+        self.grid_definition.map_type_kind()
+    }
+
+    /// 管理部 - 格子系定義 - 地図種別の名前を返す。
+    ///
+    /// # 戻り値
+    ///
+    /// 地図種別の名前、既知の地図種別でない場合は`不明`
+    pub fn map_type_name(&self) -> &'static str {
+        match self.map_type_kind() {
+            MapType::LatitudeLongitude => "緯度・経度格子座標系",
+            MapType::Other(_) => "不明",
+        }
     }
 
-    /// 管理部 - 格子系定義 - 最北西端の緯度を10e-6度単位で返す。
+    /// 管理部 - 格子系定義 - 最北西端の格子の中心の緯度を10e-6度単位で返す。
     pub fn grid_start_latitude(&self) -> u32 {
-        self.grid_definition_part.start_grid_latitude
+        self.grid_definition.start_grid_latitude
     }
 
-    /// 管理部 - 格子系定義 - 最北西端の経度を10e-6度単位で返す。
+    /// 管理部 - 格子系定義 - 最北西端の格子の中心の経度を10e-6度単位で返す。
     pub fn grid_start_longitude(&self) -> u32 {
-        self.grid_definition_part.start_grid_longitude
+        self.grid_definition.start_grid_longitude
     }
 
     /// 管理部 - 格子系定義 - 格子の幅を10e-6度単位で返す。
     pub fn grid_width(&self) -> u32 {
-        self.grid_definition_part.grid_width
+        self.grid_definition.grid_width
     }
 
     /// 管理部 - 格子系定義 - 格子の高さを10e-6度単位で返す。
     pub fn grid_height(&self) -> u32 {
-        self.grid_definition_part.grid_height
+        self.grid_definition.grid_height
+    }
+
+    /// 管理部 - 格子系定義 - 最北西端の格子の中心の緯度を度単位で返す。
+    pub fn grid_start_latitude_deg(&self) -> f64 {
+        to_degrees(self.grid_definition.start_grid_latitude)
+    }
+
+    /// 管理部 - 格子系定義 - 最北西端の格子の中心の経度を度単位で返す。
+    pub fn grid_start_longitude_deg(&self) -> f64 {
+        to_degrees(self.grid_definition.start_grid_longitude)
+    }
+
+    /// 管理部 - 格子系定義 - 格子の幅を度単位で返す。
+    pub fn grid_width_deg(&self) -> f64 {
+        to_degrees(self.grid_definition.grid_width)
+    }
+
+    /// 管理部 - 格子系定義 - 格子の高さを度単位で返す。
+    pub fn grid_height_deg(&self) -> f64 {
+        to_degrees(self.grid_definition.grid_height)
+    }
+
+    /// 格子の幅と高さを、メートル単位の概算値で返す。
+    ///
+    /// 地球を半径6,371kmの球とみなして、観測範囲の中央の緯度における格子の幅と高さを計算する。
+    /// 格子の幅は緯度によって変化するため、観測範囲の中央以外の格子の幅とは一致しない。
+    /// 開いたRAPファイルの解像度（5kmや1kmなど）を確認するときに使用する。
+    ///
+    /// # 戻り値
+    ///
+    /// `(格子の幅, 格子の高さ)`（メートル）
+    pub fn approx_resolution_meters(&self) -> (f64, f64) {
+        let (_, min_lat, _, max_lat) = self.grid_bounds();
+        let center_lat = ((min_lat + max_lat) / 2.0).to_radians();
+        let width = EARTH_RADIUS_METERS * self.grid_width_deg().to_radians() * center_lat.cos();
+        let height = EARTH_RADIUS_METERS * self.grid_height_deg().to_radians();
+
+        (width, height)
     }
 
     /// 管理部 - 格子系定義 - 観測範囲の経度方向の格子数を返す。
     pub fn number_of_h_grids(&self) -> u16 {
-        self.grid_definition_part.number_of_h_grids
+        self.grid_definition.number_of_h_grids
     }
 
     /// 管理部 - 格子系定義 - 観測範囲の緯度方向の格子数を返す。
     pub fn number_of_v_grids(&self) -> u16 {
-        self.grid_definition_part.number_of_v_grids
+        self.grid_definition.number_of_v_grids
+    }
+
+    /// 観測範囲の格子数を返す。
+    pub fn grid_cell_count(&self) -> usize {
+        self.number_of_h_grids() as usize * self.number_of_v_grids() as usize
+    }
+
+    /// 観測範囲全体の経度と緯度の範囲を度単位で返す。
+    ///
+    /// 格子系定義の最初の緯度と経度は最北西端の格子の中心を示すため、
+    /// 観測範囲は、両端の格子の中心から格子の幅と高さの半分だけ広げた範囲となる。
+    ///
+    /// # 戻り値
+    ///
+    /// `(最小経度, 最小緯度, 最大経度, 最大緯度)`
+    pub fn grid_bounds(&self) -> (f64, f64, f64, f64) {
+        let gd = &self.grid_definition;
+        let start_longitude = self.grid_start_longitude_deg();
+        let start_latitude = self.grid_start_latitude_deg();
+        let width = self.grid_width_deg();
+        let height = self.grid_height_deg();
+        let min_lon = start_longitude - width / 2.0;
+        let max_lon = start_longitude + width * (gd.number_of_h_grids as f64 - 0.5);
+        let max_lat = start_latitude + height / 2.0;
+        let min_lat = start_latitude - height * (gd.number_of_v_grids as f64 - 0.5);
+
+        (min_lon, min_lat, max_lon, max_lat)
+    }
+
+    /// 観測範囲のすべての格子の中心の座標を、観測値を読み込まずに返す。
+    ///
+    /// 格子は`value_iterator`と同様に、最北西端の格子から経度方向、緯度方向の優先順位で返す。
+    /// 観測日時によらず格子の座標は同じであるため、格子のジオメトリを1度だけ生成して、
+    /// 観測値と行と列のインデックスで結合するときに使用する。
+    ///
+    /// # 戻り値
+    ///
+    /// `(北からの行のインデックス, 西からの列のインデックス, 経度, 緯度)`を返すイテレーター
+    pub fn cell_coordinates(&self) -> impl Iterator<Item = (u16, u16, f64, f64)> {
+        let gd = self.grid_definition;
+        // `RapValueIterator`と同じ座標を返すように、10e-6度単位で計算してから度単位に変換する
+        (0..gd.number_of_v_grids).flat_map(move |row| {
+            let latitude = gd.start_grid_latitude as i64 - gd.grid_height as i64 * row as i64;
+            (0..gd.number_of_h_grids).map(move |col| {
+                let longitude = gd.start_grid_longitude as i64 + gd.grid_width as i64 * col as i64;
+                (
+                    row,
+                    col,
+                    longitude as f64 / 1_000_000.0,
+                    latitude as f64 / 1_000_000.0,
+                )
+            })
+        })
+    }
+
+    /// 引数で指定された座標を含む格子の、記録順のインデックスを返す。
+    ///
+    /// インデックスは、最北西端の格子から経度方向、緯度方向の優先順位で数えた順番であり、
+    /// `to_grid`メソッドが返す`Vec`のインデックスと一致する。
+    ///
+    /// # 引数
+    ///
+    /// * `longitude` - 経度（度）
+    /// * `latitude` - 緯度（度）
+    ///
+    /// # 戻り値
+    ///
+    /// 格子のインデックス、座標が観測範囲外の場合は`None`
+    pub fn cell_index(&self, longitude: f64, latitude: f64) -> Option<usize> {
+        locate_cell(
+            self.grid_bounds(),
+            (self.grid_width_deg(), self.grid_height_deg()),
+            (self.number_of_h_grids(), self.number_of_v_grids()),
+            longitude,
+            latitude,
+        )
     }
 
     /// 管理部 - 圧縮方法、観測値表 - 圧縮方法を返す。
@@ -135,70 +998,467 @@ impl RapReader {
         self.compression_part.compression_method
     }
 
+    /// 管理部 - 圧縮方法、観測値表 - 圧縮方法を`CompressionMethod`で返す。
+    pub fn compression_method_kind(&self) -> CompressionMethod {
+        CompressionMethod::from(self.compression_method())
+    }
+
+    /// 管理部 - 圧縮方法、観測値表 - 圧縮方法の名前を返す。
+    ///
+    /// # 戻り値
+    ///
+    /// 圧縮方法の名前、既知の圧縮方法でない場合は`不明`
+    pub fn compression_method_name(&self) -> &'static str {
+        match self.compression_method_kind() {
+            CompressionMethod::RunLength => "ラン・レングス符号圧縮",
+            CompressionMethod::Other(_) => "不明",
+        }
+    }
+
     /// 管理部 - 圧縮方法、観測値表 - レベルの数を返す。
     pub fn number_of_levels(&self) -> u16 {
         self.compression_part.number_of_levels
     }
 
-    /// 管理部 - 圧縮方法、観測値表 - レベル別の観測値を返す。
-    pub fn value_by_levels(&self) -> &[u16] {
-        &self.compression_part.value_by_levels
+    /// 管理部 - 圧縮方法、観測値表 - レベル別の観測値を返す。
+    pub fn value_by_levels(&self) -> &[u16] {
+        &self.compression_part.value_by_levels
+    }
+
+    /// 引数で指定されたレベルの降水量を返す。
+    ///
+    /// # 引数
+    ///
+    /// * `level` - レベル
+    ///
+    /// # 戻り値
+    ///
+    /// レベルの降水量、レベルが存在しない場合またはレベルの観測値が欠測値の場合は`None`
+    pub fn rainfall_for_level(&self, level: u8) -> Option<Rainfall> {
+        self.value_by_levels()
+            .get(level as usize)
+            .copied()
+            .and_then(Rainfall::new)
+    }
+
+    /// 管理部 - レベル、反復数表 - レベルと反復数の組み合わせの数を返す。
+    pub fn number_of_level_repetitions(&self) -> u16 {
+        self.level_repetitions_part.number_of_level_repetitions
+    }
+
+    /// 管理部 - レベル、反復数表 - レベルと反復数の組み合わせを返す。
+    pub fn level_repetitions(&self) -> &[LevelRepetition] {
+        &self.level_repetitions_part.level_repetitions
+    }
+
+    /// 観測値表とレベル反復表の整合性を検証する。
+    ///
+    /// レベル反復表に記録されているすべてのレベルが、観測値表に存在することを確認する。
+    /// 圧縮データに直接記録されているレベルは、圧縮データを展開するときに検証する。
+    ///
+    /// # 戻り値
+    ///
+    /// 観測値表とレベル反復表が整合している場合は`()`
+    pub fn validate_tables(&self) -> RapReaderResult<()> {
+        let number_of_levels = self.value_by_levels().len();
+        for (index, lr) in self.level_repetitions().iter().enumerate() {
+            if number_of_levels <= lr.level as usize {
+                return Err(RapReaderError::InvalidLevelRepetition {
+                    index,
+                    level: lr.level,
+                    number_of_levels,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// 引数で指定された日時の観測データの属性を返却する。
+    ///
+    /// 呼び出すたびにRAPファイルを開くため、複数のイテレーターを同時に使用できる。
+    /// 日時ごとに観測値を順に読み込む場合は、ファイルを開く回数が少ない`value_iterator_shared`
+    /// メソッドを使用できる。
+    /// 圧縮データの大きさが0の場合は、格子数分の観測値を返せないため`RapReaderError::EmptyBlock`を返す。
+    ///
+    /// # 引数
+    ///
+    /// * `dt` - 観測データの属性を取得したい日時
+    ///
+    /// # 戻り値
+    ///
+    /// 観測データの属性を格納した`DataAttribute`
+    pub fn value_iterator(&self, dt: PrimitiveDateTime) -> RapReaderResult<RapValueIterator<'_>> {
+        let dp = self.resolve_data_property(dt)?;
+        let reader = self.source.open()?;
+
+        self.build_value_iterator(dp, reader)
+    }
+
+    /// 引数で指定された日時の観測値を、観測値表で観測値に変換する前のレベルで走査するイテレーターを返す。
+    ///
+    /// 観測値は`value_iterator`と同様に、最北西端の格子から経度方向、緯度方向の優先順位で返す。
+    /// 降水強度の階級など、観測値よりもレベルを扱う方が都合の良い場合に使用する。
+    ///
+    /// # 引数
+    ///
+    /// * `dt` - 観測値を取得する日時
+    ///
+    /// # 戻り値
+    ///
+    /// `(経度, 緯度, レベル)`を記録順に返すイテレーター（欠測値のレベルは`None`）
+    pub fn level_iterator(
+        &self,
+        dt: PrimitiveDateTime,
+    ) -> RapReaderResult<impl Iterator<Item = RapReaderResult<(f64, f64, Option<u8>)>> + '_> {
+        let mut iterator = self.value_iterator(dt)?;
+
+        Ok(std::iter::from_fn(move || {
+            let lv = iterator.next()?;
+            let level = iterator.current_level;
+            Some(lv.map(|lv| (lv.longitude, lv.latitude, lv.value.map(|_| level))))
+        }))
+    }
+
+    /// 引数で指定されたインデックスの観測値を走査するイテレーターを返す。
+    ///
+    /// インデックスは、データ部へのインデックスに記録されている順番である。
+    ///
+    /// # 引数
+    ///
+    /// * `index` - 観測値を取得するインデックス
+    ///
+    /// # 戻り値
+    ///
+    /// 観測値を記録順に走査して返すイテレーター
+    pub fn value_iterator_by_index(&self, index: usize) -> RapReaderResult<RapValueIterator<'_>> {
+        let dp = self.resolve_data_property_at(index)?;
+        let reader = self.source.open()?;
+
+        self.build_value_iterator(dp, reader)
+    }
+
+    /// 引数で指定された期間に記録されている観測値を走査するイテレーターを、日時ごとに返す。
+    ///
+    /// 開始日時と終了日時を含む期間に記録されている日時を、データ部へのインデックスの順に返す。
+    /// 期間内でも観測データが記録されていない日時は返さないため、観測間隔が30分のRAPファイルも扱える。
+    /// 観測値を走査するイテレーターは`value_iterator_shared`で構築するため、
+    /// 前のイテレーターを破棄してから次のイテレーターを取得すると、開いているRAPファイルを再利用する。
+    ///
+    /// # 引数
+    ///
+    /// * `start` - 期間の開始日時
+    /// * `end` - 期間の終了日時
+    ///
+    /// # 戻り値
+    ///
+    /// 観測日時と、その日時の観測値を走査するイテレーターを返すイテレーター
+    pub fn value_iterators_between(
+        &self,
+        start: PrimitiveDateTime,
+        end: PrimitiveDateTime,
+    ) -> impl Iterator<Item = (PrimitiveDateTime, RapReaderResult<RapValueIterator<'_>>)> {
+        self.data_properties()
+            .iter()
+            .map(|dp| dp.observation_date_time)
+            .filter(move |dt| start <= *dt && *dt <= end)
+            .map(|dt| (dt, self.value_iterator_shared(dt)))
+    }
+
+    /// 引数で指定された日時の観測値を走査するイテレーターを、開いているRAPファイルを再利用して返す。
+    ///
+    /// イテレーターを破棄すると、イテレーターが使用していたRAPファイル・リーダーを`RapReader`に戻して、
+    /// 次に呼び出されたときに再利用する。
+    /// イテレーターが使用中にこのメソッドを呼び出した場合は、新しくRAPファイルを開くため、
+    /// 複数のイテレーターを同時に使用することもできる。
+    ///
+    /// # 引数
+    ///
+    /// * `dt` - 観測値を取得する日時
+    ///
+    /// # 戻り値
+    ///
+    /// 観測値を記録順に走査して返すイテレーター
+    pub fn value_iterator_shared(
+        &self,
+        dt: PrimitiveDateTime,
+    ) -> RapReaderResult<RapValueIterator<'_>> {
+        let dp = self.resolve_data_property(dt)?;
+        let shared_reader = self
+            .shared_reader
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .take();
+        let reader = match shared_reader {
+            Some(reader) => reader,
+            None => self.source.open()?,
+        };
+        let mut iterator = self.build_value_iterator(dp, reader)?;
+        iterator.shared_reader = Some(&self.shared_reader);
+
+        Ok(iterator)
+    }
+
+    /// 引数で指定された観測データの圧縮データが記録されている位置にリーダーを移動して、
+    /// 観測値を走査するイテレーターを構築する。
+    ///
+    /// 同じ日時の観測データが複数記録されている場合があるため、日時ではなく観測データの属性で指定する。
+    fn build_value_iterator(
+        &self,
+        dp: &DataProperty,
+        mut reader: RapDataReader,
+    ) -> RapReaderResult<RapValueIterator<'_>> {
+        self.ensure_value_iterator_supported()?;
+        if dp.compressed_data_size == 0 {
+            return Err(RapReaderError::EmptyBlock(dp.observation_date_time));
+        }
+
+        // 引数の日時の圧縮データが記録されている位置まで、ファイルの読み込み位置を移動
+        reader.seek(SeekFrom::Start(dp.compressed_data_start_position()))?;
+
+        // 観測値を記録順に走査して返すイテレーターを構築
+        Ok(RapValueIterator::from_grid_definition(
+            reader,
+            dp.compressed_data_size as usize,
+            &self.grid_definition,
+            self.value_by_levels(),
+            self.level_repetitions(),
+        ))
+    }
+
+    /// 引数で指定された日時の、複数の座標の観測値を返す。
+    ///
+    /// 観測値を1度だけ展開して、座標ごとに座標を含む格子の観測値を返す。
+    ///
+    /// # 引数
+    ///
+    /// * `dt` - 観測値を取得する日時
+    /// * `points` - `(経度, 緯度)`で表現した座標（度）
+    ///
+    /// # 戻り値
+    ///
+    /// 座標の順に観測値を格納した`Vec`（欠測値または観測範囲外の座標は`None`）
+    pub fn sample_at(
+        &self,
+        dt: PrimitiveDateTime,
+        points: &[(f64, f64)],
+    ) -> RapReaderResult<Vec<Option<u16>>> {
+        let grid = self.to_grid(dt)?;
+
+        Ok(points
+            .iter()
+            .map(|&(longitude, latitude)| {
+                self.cell_index(longitude, latitude)
+                    .and_then(|index| grid.get(index).copied().flatten())
+            })
+            .collect())
+    }
+
+    /// 引数で指定された日時の観測値を、記録順に格納した`Vec`を返す。
+    ///
+    /// 観測値は、最北西端の格子から経度方向、緯度方向の優先順位で格納され、欠測値は`None`である。
+    /// 展開した観測値の数が格子数と一致しない場合は、`RapReaderError::GridSizeMismatch`を返す。
+    /// 圧縮データの大きさが0の場合は、`RapReaderError::EmptyBlock`を返す。
+    ///
+    /// # 引数
+    ///
+    /// * `dt` - 観測値を取得する日時
+    ///
+    /// # 戻り値
+    ///
+    /// 観測値を格納した`Vec`
+    pub fn to_grid(&self, dt: PrimitiveDateTime) -> RapReaderResult<Vec<Option<u16>>> {
+        match self.compression_method_kind() {
+            CompressionMethod::RunLength => self.to_grid_with(dt, &RunLengthDecompressor),
+            CompressionMethod::Other(method) => {
+                Err(RapReaderError::CompressionMethodUnsupported(method))
+            }
+        }
+    }
+
+    /// 引数で指定された日時の観測値を、引数で指定された格子の並び順で格納した`Vec`を返す。
+    ///
+    /// `Origin::TopLeft`を指定した場合は`to_grid`と同じく、最北西端の格子から格納する。
+    /// `Origin::BottomLeft`を指定した場合は、最南西端の格子から経度方向、北に向かって緯度方向の優先順位で格納する。
+    ///
+    /// # 引数
+    ///
+    /// * `dt` - 観測値を取得する日時
+    /// * `origin` - 格子の並び順の原点
+    ///
+    /// # 戻り値
+    ///
+    /// 観測値を格納した`Vec`
+    pub fn to_grid_with_origin(
+        &self,
+        dt: PrimitiveDateTime,
+        origin: Origin,
+    ) -> RapReaderResult<Vec<Option<u16>>> {
+        let mut values = self.to_grid(dt)?;
+        origin.reorder(&mut values, self.number_of_h_grids() as usize);
+
+        Ok(values)
+    }
+
+    /// 引数で指定された日時の観測値を、引数で指定された`Vec`に記録順に格納する。
+    ///
+    /// `to_grid`と異なり、`buf`を空にしてから観測値を格納するため、
+    /// 多数の日時やファイルの観測値を順に展開するときに、確保済みの領域を再利用できる。
+    /// 展開した観測値の数が格子数と一致しない場合は、`RapReaderError::GridSizeMismatch`を返す。
+    ///
+    /// # 引数
+    ///
+    /// * `dt` - 観測値を取得する日時
+    /// * `buf` - 観測値を格納する`Vec`（欠測値は`None`）
+    pub fn decode_into(
+        &self,
+        dt: PrimitiveDateTime,
+        buf: &mut Vec<Option<u16>>,
+    ) -> RapReaderResult<()> {
+        if let CompressionMethod::Other(method) = self.compression_method_kind() {
+            return Err(RapReaderError::CompressionMethodUnsupported(method));
+        }
+        let compressed = self.compressed_block(dt)?;
+        if compressed.is_empty() {
+            return Err(RapReaderError::EmptyBlock(dt));
+        }
+
+        decode_grid_into(
+            &compressed,
+            self.value_by_levels(),
+            self.level_repetitions(),
+            self.number_of_h_grids(),
+            self.number_of_v_grids(),
+            buf,
+        )
+        .map_err(RapReaderError::from)
+    }
+
+    /// 引数で指定された日時の観測値を、mm単位の`f32`で記録順に隙間なく格納した`Vec`を返す。
+    ///
+    /// 観測値は`to_grid`と同様に、最北西端の格子から経度方向、緯度方向の優先順位で格納する。
+    /// テクスチャとしてGPUに転送するなど、グラフィックスのコードに観測値を渡すときに使用する。
+    ///
+    /// # 引数
+    ///
+    /// * `dt` - 観測値を取得する日時
+    /// * `missing` - 欠測値の格子に格納する値（`f32::NAN`など）
+    ///
+    /// # 戻り値
+    ///
+    /// `(観測値を格納した`Vec`, (経度方向の格子数, 緯度方向の格子数))`
+    pub fn to_flat_f32(
+        &self,
+        dt: PrimitiveDateTime,
+        missing: f32,
+    ) -> RapReaderResult<(Vec<f32>, (u16, u16))> {
+        let values = self
+            .to_grid(dt)?
+            .into_iter()
+            .map(|value| match value {
+                Some(value) => (value as f64 * MM_PER_VALUE) as f32,
+                None => missing,
+            })
+            .collect();
+
+        Ok((values, (self.number_of_h_grids(), self.number_of_v_grids())))
+    }
+
+    /// 引数で指定された`Decompressor`で圧縮データを展開して、観測値を記録順に格納した`Vec`を返す。
+    ///
+    /// 組み込みで展開できない圧縮方法のファイルから観測値を取得するときに使用する。
+    /// 展開した観測値の数が格子数と一致しない場合は、`RapReaderError::GridSizeMismatch`を返す。
+    ///
+    /// # 引数
+    ///
+    /// * `dt` - 観測値を取得する日時
+    /// * `decompressor` - 圧縮データを展開する`Decompressor`
+    ///
+    /// # 戻り値
+    ///
+    /// 観測値を格納した`Vec`
+    pub fn to_grid_with<D>(
+        &self,
+        dt: PrimitiveDateTime,
+        decompressor: &D,
+    ) -> RapReaderResult<Vec<Option<u16>>>
+    where
+        D: Decompressor + ?Sized,
+    {
+        let compressed = self.compressed_block(dt)?;
+        if compressed.is_empty() {
+            return Err(RapReaderError::EmptyBlock(dt));
+        }
+        let values = decompressor.decompress(
+            &compressed,
+            &self.grid_definition,
+            self.value_by_levels(),
+            self.level_repetitions(),
+        )?;
+        if values.len() != self.grid_cell_count() {
+            return Err(RapReaderError::GridSizeMismatch {
+                expected: self.grid_cell_count(),
+                actual: values.len(),
+            });
+        }
+
+        Ok(values)
     }
 
-    /// 管理部 - レベル、反復数表 - レベルと反復数の組み合わせの数を返す。
-    pub fn number_of_level_repetitions(&self) -> u16 {
-        self.level_repetitions_part.number_of_level_repetitions
-    }
+    /// 観測値を走査するイテレーターが、座標を計算して圧縮データを展開できるか確認する。
+    fn ensure_value_iterator_supported(&self) -> RapReaderResult<()> {
+        if let MapType::Other(map_type) = self.map_type_kind() {
+            return Err(RapReaderError::MapTypeUnsupported(map_type));
+        }
+        if let CompressionMethod::Other(method) = self.compression_method_kind() {
+            return Err(RapReaderError::CompressionMethodUnsupported(method));
+        }
 
-    /// 管理部 - レベル、反復数表 - レベルと反復数の組み合わせを返す。
-    pub fn level_repetitions(&self) -> &[LevelRepetition] {
-        &self.level_repetitions_part.level_repetitions
+        Ok(())
     }
 
-    /// 引数で指定された日時の観測データの属性を返却する。
+    /// 引数で指定された日時の圧縮データを、展開せずに返す。
+    ///
+    /// 返すバイト列は、データ部の圧縮後の大きさの直後から、圧縮後の大きさだけ読み込んだバイト列であり、
+    /// `decode_grid`などで展開できる。
     ///
     /// # 引数
     ///
-    /// * `dt` - 観測データの属性を取得したい日時
+    /// * `dt` - 圧縮データを取得する日時
     ///
     /// # 戻り値
     ///
-    /// 観測データの属性を格納した`DataAttribute`
-    pub fn value_iterator(&self, dt: PrimitiveDateTime) -> RapReaderResult<RapValueIterator<'_>> {
-        let dp = self
-            .data_index_part
-            .data_properties
-            .iter()
-            .find(|dp| dp.observation_date_time == dt)
-            .ok_or(RapReaderError::DataDoesNotRecorded(dt))?;
-
-        let file = OpenOptions::new()
-            .read(true)
-            .open(&self.path)
-            .map_err(|e| RapReaderError::Open(format!("{e}")))?;
-        let mut reader = BufReader::new(file);
+    /// 圧縮データ
+    pub fn compressed_block(&self, dt: PrimitiveDateTime) -> RapReaderResult<Vec<u8>> {
+        let dp = self.resolve_data_property(dt)?;
+        let mut reader = self.source.open()?;
 
-        // 引数の日時の圧縮データが記録されている位置まで、ファイルの読み込み位置を移動
-        reader
-            .seek(SeekFrom::Start(dp.data_start_position as u64 + 4))
-            .map_err(|e| {
-                RapReaderError::Unexpected(format!(
-                    "圧縮データが記録されている位置へのシークに失敗しました。{e}"
-                ))
-            })?;
+        read_compressed_data(&mut reader, dp)
+    }
 
-        // 観測値を記録順に走査して返すイテレーターを構築
-        Ok(RapValueIterator::new(
-            reader,
-            dp.compressed_data_size as usize,
-            self.grid_start_latitude(),
-            self.grid_start_longitude(),
-            self.number_of_h_grids(),
-            self.grid_height(),
-            self.grid_width(),
-            self.value_by_levels(),
-            self.level_repetitions(),
-        ))
+    /// ファイルに記録されているすべての日時の観測値を、日時と組み合わせて順に返すイテレーターを返す。
+    ///
+    /// 観測値は、データ部へのインデックスに記録されている順に日時ごとに走査する。
+    /// 日時ごとに`RapValueIterator`を構築して、その日時の圧縮データが記録されている位置から読み込む。
+    ///
+    /// # 戻り値
+    ///
+    /// 観測日時と観測値を順に返すイテレーター
+    pub fn all_values(
+        &self,
+    ) -> impl Iterator<Item = RapReaderResult<(PrimitiveDateTime, LocationValue)>> + '_ {
+        self.data_properties()
+            .iter()
+            .enumerate()
+            .flat_map(move |(index, dp)| {
+                let dt = dp.observation_date_time;
+                let values: Box<dyn Iterator<Item = _>> = match self.value_iterator_by_index(index)
+                {
+                    Ok(iterator) => Box::new(iterator.map(move |lv| lv.map(|lv| (dt, lv)))),
+                    Err(e) => Box::new(std::iter::once(Err(e))),
+                };
+                values
+            })
     }
 
     /// ファイルの情報を整形して出力する。
@@ -211,12 +1471,120 @@ impl RapReader {
         W: Write,
     {
         print_management_part(writer, self)?;
-        print_data_part(writer, self.data_properties())?;
+        print_data_part(writer, &self.resolved_data_properties())?;
 
         Ok(())
     }
 }
 
+/// ログに出力するための、管理部の概要を1行で表示する。
+///
+/// `RAP[解析雨量] 2001-04-01 01:00..2001-04-02 00:00, 24 obs, 2560x3360 grid @ 0.0125°`のように表示する。
+/// 観測要素は最初に記録されている観測データの観測要素である。
+impl std::fmt::Display for RapReader {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "RAP")?;
+        if let Some(dp) = self.data_properties().first() {
+            write!(f, "[{}]", dp.observation_element_typed())?;
+        }
+        if let Some((first, last)) = self.observation_range() {
+            let first = first.format(SUMMARY_DATETIME_FMT).unwrap();
+            let last = last.format(SUMMARY_DATETIME_FMT).unwrap();
+            write!(f, " {first}..{last}")?;
+        }
+        write!(
+            f,
+            ", {} obs, {}x{} grid @ {}°",
+            self.data_properties().len(),
+            self.number_of_h_grids(),
+            self.number_of_v_grids(),
+            self.grid_width_deg()
+        )
+    }
+}
+
+/// RAPファイルの読み込み元
+#[derive(Debug, Clone)]
+enum RapSource {
+    /// ファイル
+    File {
+        /// ファイルのパス
+        path: Arc<Path>,
+        /// ファイルを読み込むバッファーのバイト数
+        buffer_capacity: usize,
+    },
+    /// メモリ上のバイト列
+    Bytes {
+        /// RAPファイルのバイト列
+        bytes: Arc<[u8]>,
+        /// gzipで圧縮されたファイルを展開した場合、展開元のファイルのパス
+        path: Option<Arc<Path>>,
+    },
+}
+
+impl RapSource {
+    /// 読み込み元を先頭から読み込むリーダーを返す。
+    fn open(&self) -> RapReaderResult<RapDataReader> {
+        match self {
+            Self::File {
+                path,
+                buffer_capacity,
+            } => {
+                let file = OpenOptions::new()
+                    .read(true)
+                    .open(path)
+                    .map_err(|e| RapReaderError::Open(format!("{e}")))?;
+                Ok(RapDataReader::File(BufReader::with_capacity(
+                    *buffer_capacity,
+                    file,
+                )))
+            }
+            Self::Bytes { bytes, .. } => Ok(RapDataReader::Bytes(Cursor::new(Arc::clone(bytes)))),
+        }
+    }
+}
+
+/// RAPファイル・リーダー
+///
+/// ファイルまたはメモリ上のバイト列から、RAPファイルを読み込む。
+#[derive(Debug)]
+pub enum RapDataReader {
+    /// ファイル
+    File(FileReader),
+    /// メモリ上のバイト列
+    Bytes(BytesReader),
+}
+
+impl From<FileReader> for RapDataReader {
+    fn from(value: FileReader) -> Self {
+        Self::File(value)
+    }
+}
+
+impl From<BytesReader> for RapDataReader {
+    fn from(value: BytesReader) -> Self {
+        Self::Bytes(value)
+    }
+}
+
+impl Read for RapDataReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        match self {
+            Self::File(reader) => reader.read(buf),
+            Self::Bytes(reader) => reader.read(buf),
+        }
+    }
+}
+
+impl Seek for RapDataReader {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        match self {
+            Self::File(reader) => reader.seek(pos),
+            Self::Bytes(reader) => reader.seek(pos),
+        }
+    }
+}
+
 /// コメント
 #[derive(Debug, Clone)]
 struct CommentPart {
@@ -231,16 +1599,19 @@ struct CommentPart {
 }
 
 /// データ部へのインデックス
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct DataProperty {
     /// 観測日時
     ///
+    /// 観測日時は日本標準時（JST）であり、観測値を積算した期間の終了日時を示す。
     /// RAPファイルには、0時から1時までのデータは、1時として記録されている。
     /// よって、24観測データが記録されているRAPファイルに記録されている観測日時は、
     /// 1時から翌日の0時の範囲である。
     pub observation_date_time: PrimitiveDateTime,
 
     /// 観測要素
+    ///
+    /// 既知の観測要素は、`observation_element_typed`メソッドで`ObservationElement`として取得できる。
     pub observation_element: u16,
 
     /// 観測日時の観測データが記録されているファイルの先頭からのバイト位置
@@ -256,6 +1627,67 @@ pub struct DataProperty {
     pub number_of_amedas: u32,
 }
 
+impl DataProperty {
+    /// 観測要素を`ObservationElement`で返す。
+    pub fn observation_element_typed(&self) -> ObservationElement {
+        ObservationElement::from(self.observation_element)
+    }
+
+    /// 圧縮データが記録されているファイルの先頭からのバイト位置を返す。
+    ///
+    /// データ部の先頭には圧縮後の大きさが記録されているため、圧縮データはその直後から記録されている。
+    pub fn compressed_data_start_position(&self) -> u64 {
+        self.data_start_position as u64 + COMPRESSED_DATA_SIZE_BYTES
+    }
+
+    /// 圧縮データの末尾の次のバイトの、ファイルの先頭からのバイト位置を返す。
+    ///
+    /// `RapReader::open_lazy`で開いた`RapReader`の`data_properties`メソッドが返す属性は、
+    /// 圧縮後の大きさが記録されていないため、圧縮データの先頭のバイト位置を返す。
+    pub fn compressed_data_end_position(&self) -> u64 {
+        self.compressed_data_start_position() + self.compressed_data_size as u64
+    }
+
+    /// 観測値を積算した期間の開始日時を返す。
+    ///
+    /// 観測値は、観測間隔の期間に積算した降水量である。
+    /// RAPファイルに記録されている観測日時は積算期間の終了日時であるため、
+    /// 積算期間の開始日時は、観測日時から観測間隔だけ前の日時となる。
+    /// 例えば、24観測データが記録されているRAPファイルの1時の観測値は、0時から1時までに積算した降水量である。
+    ///
+    /// # 引数
+    ///
+    /// * `observation_times` - 1日の観測回数
+    ///
+    /// # 戻り値
+    ///
+    /// 積算期間の開始日時（日本標準時）
+    pub fn interval_start(&self, observation_times: ObservationTimes) -> PrimitiveDateTime {
+        self.observation_date_time - observation_times.interval()
+    }
+
+    /// 観測値を積算した期間の終了日時を返す。
+    ///
+    /// 積算期間の終了日時は、RAPファイルに記録されている観測日時と一致する。
+    ///
+    /// # 戻り値
+    ///
+    /// 積算期間の終了日時（日本標準時）
+    pub fn interval_end(&self) -> PrimitiveDateTime {
+        self.observation_date_time
+    }
+
+    /// 観測日時を日本標準時（UTC+09:00）のオフセット付き日時で返す。
+    pub fn observation_datetime_jst(&self) -> OffsetDateTime {
+        self.observation_date_time.assume_offset(JST_OFFSET)
+    }
+
+    /// 観測日時を協定世界時（UTC）のオフセット付き日時で返す。
+    pub fn observation_datetime_utc(&self) -> OffsetDateTime {
+        self.observation_datetime_jst().to_offset(UtcOffset::UTC)
+    }
+}
+
 impl Default for DataProperty {
     fn default() -> Self {
         Self {
@@ -269,6 +1701,27 @@ impl Default for DataProperty {
     }
 }
 
+/// データ部の配置
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BlockLayout {
+    /// 観測日時
+    pub datetime: PrimitiveDateTime,
+
+    /// データ部の先頭のファイルの先頭からのバイト位置
+    ///
+    /// データ部の先頭には、圧縮したデータのサイズが4バイトで記録されている。
+    pub data_start_position: u32,
+
+    /// 圧縮した観測データのサイズ
+    pub compressed_data_size: u32,
+
+    /// レーダー運用状況
+    pub radar_operation_statuses: u64,
+
+    /// 解析に使用したアメダスの総数
+    pub number_of_amedas: u32,
+}
+
 /// データ部へのインデックス
 #[derive(Debug, Clone)]
 struct DataIndexPart {
@@ -283,31 +1736,43 @@ struct DataIndexPart {
 }
 
 /// 格子系定義
-#[derive(Debug, Clone, Copy)]
-struct GridDefinitionPart {
+///
+/// 複数のRAPファイルの格子系定義が一致するか比較できる。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct GridDefinition {
     /// 地図種別
     ///
-    /// 1: 解析雨量
-    map_type: u16,
+    /// 1: 解析雨量（緯度・経度格子座標系）
+    ///
+    /// 既知の地図種別は`MapType`を参照。
+    pub map_type: u16,
 
-    /// 最初の緯度と軽度
+    /// 最初の緯度と経度
     ///
     /// 10e-6度単位で表現する。
-    /// 最初のデータは観測範囲の北西端である。
+    /// 最初のデータは観測範囲の最北西端の格子であり、緯度と経度は格子の隅ではなく、
+    /// 格子の中心の座標を示す。
     /// 最初のデータ以後は、経度方向に西から東にデータが記録され、東端に達したとき、
     /// 格子1つ分だけ南で、西端の格子のデータが記録されている。
-    start_grid_latitude: u32,
-    start_grid_longitude: u32,
+    pub start_grid_latitude: u32,
+    pub start_grid_longitude: u32,
 
     /// 横方向と縦方向の格子間隔
     ///
     /// 10e-6度単位で表現する。
-    grid_width: u32,
-    grid_height: u32,
+    pub grid_width: u32,
+    pub grid_height: u32,
 
     /// 横方向と縦方向の格子数
-    pub(crate) number_of_h_grids: u16,
-    pub(crate) number_of_v_grids: u16,
+    pub number_of_h_grids: u16,
+    pub number_of_v_grids: u16,
+}
+
+impl GridDefinition {
+    /// 地図種別を`MapType`で返す。
+    pub fn map_type_kind(&self) -> MapType {
+        MapType::from(self.map_type)
+    }
 }
 
 /// 圧縮方法、観測値表
@@ -334,9 +1799,36 @@ pub struct LevelRepetition {
     /// 反復数
     ///
     /// 記録されている値は、実際の反復数より2少ない数を格納している。
+    /// 実際の反復数は、`actual_repetition`メソッドで取得できる。
     pub repetition: u8,
 }
 
+impl LevelRepetition {
+    /// 実際の反復数を返す。
+    pub fn actual_repetition(&self) -> u16 {
+        self.repetition as u16 + REPETITION_OFFSET
+    }
+}
+
+/// 欠測値を示すレベルの観測値
+const MISSING_VALUE: u16 = u16::MAX;
+
+/// レベルの観測値が欠測値を示すか確認する。
+///
+/// # 引数
+///
+/// * `value` - レベルの観測値
+///
+/// # 戻り値
+///
+/// 欠測値を示す場合は`true`、それ以外の場合は`false`
+fn is_missing(value: u16) -> bool {
+    value == MISSING_VALUE
+}
+
+/// 記録されている反復数と実際の反復数の差
+const REPETITION_OFFSET: u16 = 2;
+
 /// レベルと反復数表
 #[derive(Debug, Clone)]
 struct LevelRepetitionsPart {
@@ -350,6 +1842,17 @@ struct LevelRepetitionsPart {
     pub(crate) level_repetitions: Vec<LevelRepetition>,
 }
 
+/// 管理部の予備の領域
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReservedRegion {
+    /// 予備の領域の名前
+    pub name: &'static str,
+    /// 予備の領域の先頭の、ファイルの先頭からの位置
+    pub position: u64,
+    /// 予備の領域に記録されているバイト列
+    pub bytes: Vec<u8>,
+}
+
 /// 1日の観測回数
 #[derive(Debug, Clone, Copy)]
 pub enum ObservationTimes {
@@ -372,48 +1875,595 @@ impl TryFrom<u32> for ObservationTimes {
         match value {
             24 => Ok(Self::Times24),
             48 => Ok(Self::Times48),
+            // RAPファイルはリトル・エンディアンで記録されているため、バイト順序を入れ替えると
+            // 観測回数になる場合は、バイト順序が入れ替わったファイルと判断する
+            _ if matches!(value.swap_bytes(), 24 | 48) => {
+                Err(RapReaderError::ByteOrderMismatch(value))
+            }
             _ => Err(RapReaderError::ObservationIntervalUnsupported(value)),
         }
     }
 }
 
-/// 地図種別
-const MAP_TYPE: u16 = 1; // 緯度・経度格子座標系
+impl ObservationTimes {
+    /// 観測間隔を返す。
+    pub fn interval(&self) -> Duration {
+        match self {
+            Self::Times24 => Duration::hours(1),
+            Self::Times48 => Duration::minutes(30),
+        }
+    }
+}
+
+impl std::fmt::Display for ObservationTimes {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Times24 => write!(f, "毎正時 (24回/日)"),
+            Self::Times48 => write!(f, "30分毎 (48回/日)"),
+        }
+    }
+}
+
+/// 観測要素
+///
+/// データ部へのインデックスに記録されている観測要素の既知のコードは次の通りである。
+///
+/// | コード | 観測要素 |
+/// |---|---|
+/// | 1 | 解析雨量 |
+///
+/// それ以外のコードは`ObservationElement::Other`で表現する。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ObservationElement {
+    /// 解析雨量
+    AnalysisRainfall,
+    /// その他の観測要素
+    Other(u16),
+}
+
+/// `u16`型から観測要素を示す`ObservationElement`に変換する。
+impl From<u16> for ObservationElement {
+    fn from(value: u16) -> Self {
+        match value {
+            1 => Self::AnalysisRainfall,
+            _ => Self::Other(value),
+        }
+    }
+}
+
+/// 観測要素を示す`ObservationElement`から`u16`型に変換する。
+impl From<ObservationElement> for u16 {
+    fn from(value: ObservationElement) -> Self {
+        match value {
+            ObservationElement::AnalysisRainfall => 1,
+            ObservationElement::Other(value) => value,
+        }
+    }
+}
+
+impl std::fmt::Display for ObservationElement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::AnalysisRainfall => write!(f, "解析雨量"),
+            Self::Other(value) => write!(f, "不明 ({value})"),
+        }
+    }
+}
+
+/// 地図種別
+///
+/// 観測値の座標を計算できる地図種別は、`MapType::LatitudeLongitude`のみである。
+/// それ以外の地図種別のファイルも格子系定義を読み込み、`RapReader::to_grid`などで格子ごとの観測値を
+/// 取得できるが、座標を伴う`RapReader::value_iterator`などは`MapTypeUnsupported`エラーを返す。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MapType {
+    /// 緯度・経度格子座標系
+    LatitudeLongitude,
+    /// その他の地図種別
+    Other(u16),
+}
+
+/// `u16`型から地図種別を示す`MapType`に変換する。
+impl From<u16> for MapType {
+    fn from(value: u16) -> Self {
+        match value {
+            1 => Self::LatitudeLongitude,
+            _ => Self::Other(value),
+        }
+    }
+}
+
+/// 地図種別を示す`MapType`から`u16`型に変換する。
+impl From<MapType> for u16 {
+    fn from(value: MapType) -> Self {
+        match value {
+            MapType::LatitudeLongitude => 1,
+            MapType::Other(value) => value,
+        }
+    }
+}
+
+/// 圧縮方法
+///
+/// 組み込みで展開できる圧縮方法は、`CompressionMethod::RunLength`のみである。
+/// それ以外の圧縮方法のファイルも管理部を読み込めるが、観測値を取得するには
+/// `RapReader::to_grid_with`で`Decompressor`を指定する必要がある。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CompressionMethod {
+    /// ラン・レングス符号圧縮
+    RunLength,
+    /// その他の圧縮方法
+    Other(u16),
+}
+
+/// `u16`型から圧縮方法を示す`CompressionMethod`に変換する。
+impl From<u16> for CompressionMethod {
+    fn from(value: u16) -> Self {
+        match value {
+            1 => Self::RunLength,
+            _ => Self::Other(value),
+        }
+    }
+}
+
+/// 圧縮方法を示す`CompressionMethod`から`u16`型に変換する。
+impl From<CompressionMethod> for u16 {
+    fn from(value: CompressionMethod) -> Self {
+        match value {
+            CompressionMethod::RunLength => 1,
+            CompressionMethod::Other(value) => value,
+        }
+    }
+}
+
+/// RapReaderエラー型
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum RapReaderError {
+    /// 予期しない例外
+    #[error("{0}")]
+    Unexpected(String),
+
+    /// ファイル・オープン・エラー
+    #[error("ファイルを開くときにエラーが発生しました。{0}")]
+    Open(String),
+
+    /// ファイル書き込みエラー
+    #[error("ファイルに書き込むときにエラーが発生しました。{0}")]
+    Write(String),
+
+    /// サポートしていない観測時間間隔
+    #[error("サポートしていない時間間隔です。`{0}`")]
+    ObservationIntervalUnsupported(u32),
+
+    /// バイト順序が入れ替わったファイル
+    #[error(
+        "データ数`{0}`のバイト順序を入れ替えると`{}`になります。RAPファイルはリトル・エンディアンで記録されている必要があります。",
+        .0.swap_bytes()
+    )]
+    ByteOrderMismatch(u32),
+
+    /// サポートしていない地図種別
+    #[error("サポートしていない地図種別です。`{0}`")]
+    MapTypeUnsupported(u16),
+
+    /// サポートしていない圧縮方法
+    #[error("サポートしていない圧縮方法です。`{0}`")]
+    CompressionMethodUnsupported(u16),
+
+    /// 指定された日付のデータが記録されていない
+    #[error("指定された日付のデータは記録されていません。`{0:?}`")]
+    DataDoesNotRecorded(PrimitiveDateTime),
+
+    /// 指定された日付のデータ部に、圧縮データが記録されていない
+    ///
+    /// 圧縮後の大きさが0のデータ部は、観測値が記録されていない仮のデータ部とみなす。
+    #[error("指定された日付のデータ部に、圧縮データが記録されていません。`{0:?}`")]
+    EmptyBlock(PrimitiveDateTime),
+
+    /// 指定されたインデックスのデータが記録されていない
+    #[error(
+        "指定されたインデックス({index})のデータは記録されていません。データ数: {number_of_data}"
+    )]
+    DataIndexOutOfRange {
+        /// 指定されたインデックス
+        index: usize,
+        /// 記録されているデータ数
+        number_of_data: usize,
+    },
+
+    /// タイルの格子数が不正
+    #[error("タイルの格子数が不正です。経度方向: {width}、緯度方向: {height}")]
+    InvalidTileSize {
+        /// タイルの経度方向の格子数
+        width: u16,
+        /// タイルの緯度方向の格子数
+        height: u16,
+    },
+
+    /// 集約する格子数が不正
+    #[error("集約する格子数が不正です。`{0}`")]
+    InvalidDownsampleFactor(u16),
+
+    /// ファイル名の日付と、記録されている観測日時の日付が一致しない
+    #[error(
+        "ファイル名の日付({expected})と、記録されている観測日時の日付({actual})が一致しません。"
+    )]
+    FileNameDateMismatch {
+        /// ファイル名から取得した日付
+        expected: Date,
+        /// 最も古い観測日時の日付
+        actual: Date,
+    },
+
+    /// ファイルに記録されている月が不正
+    #[error("ファイルに記録されている月({0})が不正です。")]
+    InvalidMonth(u8),
+
+    /// ファイルに記録されている年月日が不正
+    #[error("ファイルに記録されている年月日({year}年{month}月{day}日)が不正です。")]
+    InvalidDate {
+        /// 年
+        year: u16,
+        /// 月
+        month: u8,
+        /// 日
+        day: u8,
+    },
+
+    /// ファイルに記録されている時分が不正
+    #[error("ファイルに記録されている時分({hour}時{minute}分)が不正です。")]
+    InvalidTime {
+        /// 時
+        hour: u8,
+        /// 分
+        minute: u8,
+    },
+
+    /// 積算する観測データの数が不正
+    #[error("積算する観測データの数が不正です。`{0}`")]
+    InvalidAccumulationWindow(usize),
+
+    /// 格子数が集約する格子数で割り切れない
+    #[error(
+        "格子数が集約する格子数で割り切れません。経度方向の格子数: {number_of_h_grids}、緯度方向の格子数: {number_of_v_grids}、集約する格子数: {factor}"
+    )]
+    GridNotDivisible {
+        number_of_h_grids: u16,
+        number_of_v_grids: u16,
+        factor: u16,
+    },
+
+    /// 展開した観測値の数が、格子系定義の格子数と一致しない
+    #[error("展開した観測値の数が格子数と一致しません。格子数: {expected}、観測値の数: {actual}")]
+    GridSizeMismatch {
+        /// 格子系定義の格子数
+        expected: usize,
+        /// 展開した観測値の数
+        actual: usize,
+    },
+
+    /// 格子の緯度が0度未満になった
+    #[error(
+        "{cell_index}番目の格子の緯度が0度未満になりました。観測値の数が、緯度0度までの格子数を超えています。"
+    )]
+    LatitudeUnderflow {
+        /// 緯度が0度未満になった格子の、最北西端の格子からの記録順のインデックス
+        cell_index: usize,
+    },
+
+    /// 観測値を走査するイテレーターを、既に走査した格子に戻そうとした
+    #[error(
+        "{target}番目の格子に移動できません。イテレーターは既に{cell_index}番目の格子まで走査しています。"
+    )]
+    SkipBackward {
+        /// 移動しようとした格子の、最北西端の格子からの記録順のインデックス
+        target: usize,
+        /// 次に返す格子の、最北西端の格子からの記録順のインデックス
+        cell_index: usize,
+    },
+
+    /// 格子系定義が一致しない
+    #[error("格子系定義が一致しません。`{0:?}`、`{1:?}`")]
+    GridDefinitionMismatch(GridDefinition, GridDefinition),
+
+    /// 格子系定義の観測範囲が、有効な緯度または経度の範囲を超えている
+    #[error("格子系定義の観測範囲が、有効な緯度または経度の範囲を超えています。{reason} `{grid_definition:?}`")]
+    GridOutOfRange {
+        /// 格子系定義
+        grid_definition: GridDefinition,
+        /// 範囲を超えている理由
+        reason: String,
+    },
+
+    /// レベル反復表に観測値表に存在しないレベルが記録されている
+    #[error(
+        "レベル反復表の{index}番目に、観測値表に存在しないレベルが記録されています。レベル: {level}、レベルの数: {number_of_levels}"
+    )]
+    InvalidLevelRepetition {
+        /// レベル反復表のインデックス
+        index: usize,
+        /// 記録されているレベル
+        level: u8,
+        /// 観測値表のレベルの数
+        number_of_levels: usize,
+    },
+
+    /// データ部の構造が不正
+    #[error("{datetime:?}のデータ部の構造が不正です。{reason}")]
+    InvalidBlock {
+        /// 構造が不正なデータ部の観測日時
+        datetime: PrimitiveDateTime,
+        /// 構造が不正な理由
+        reason: String,
+    },
+
+    /// 観測値表に、観測値として妥当な最大値を超える観測値が記録されている
+    #[error("観測値表のレベル{level}の観測値({value})が、妥当な最大値({max})を超えています。")]
+    ImplausibleValue {
+        /// 最大値を超える観測値が記録されているレベル
+        level: usize,
+        /// 観測値
+        value: u16,
+        /// `RapReaderOptions::max_plausible_value`で指定した最大値
+        max: u16,
+    },
+
+    /// 観測値の展開に失敗した
+    #[error("{datetime:?}の観測値の展開に失敗しました。{source}")]
+    DecodeFailed {
+        /// 観測値の展開に失敗した観測日時
+        datetime: PrimitiveDateTime,
+        /// 観測値の展開に失敗した原因
+        source: Box<RapReaderError>,
+    },
+
+    /// 圧縮データに判別できないランレングス圧縮バイトが記録されている
+    #[error(
+        "圧縮データに判別できないバイトが見つかりました。`0x{byte:x}` (オフセット: {offset}、格子のインデックス: {cell_index})"
+    )]
+    UnknownRunLengthByte {
+        /// 判別できないバイト
+        byte: u8,
+        /// 判別できないバイトの、圧縮データの先頭からのバイト位置
+        offset: usize,
+        /// 判別できないバイトを展開した観測値を格納するはずだった格子の、最北西端の格子からの記録順のインデックス
+        cell_index: usize,
+    },
+
+    /// 圧縮データの展開エラー
+    ///
+    /// `GridSizeMismatch`と`UnknownRunLengthByte`に変換できない展開エラーを示す。
+    #[error("観測値の展開に失敗しました。{0}")]
+    Decode(#[source] DecodeError),
+
+    /// RAPファイルの解析エラー
+    #[error("{field}の読み込みに失敗しました。(オフセット: 0x{offset:X}) {source}")]
+    Parse {
+        /// 読み込みに失敗したファイルの先頭からのバイト位置
+        offset: u64,
+        /// 読み込みに失敗したフィールドの名前
+        field: &'static str,
+        /// 読み込みに失敗した原因
+        source: Arc<std::io::Error>,
+    },
+
+    /// 入出力に失敗した
+    ///
+    /// `RapReaderError`は`Clone`を実装するため、`std::io::Error`を`Arc`で保持する。
+    /// `std::io::ErrorKind`は`source.kind()`で取得できる。
+    #[error("入出力に失敗しました。{0}")]
+    Io(Arc<std::io::Error>),
+}
+
+/// `std::io::Error`を`RapReaderError::Io`に変換する。
+impl From<std::io::Error> for RapReaderError {
+    fn from(value: std::io::Error) -> Self {
+        Self::Io(Arc::new(value))
+    }
+}
+
+/// 圧縮データの展開エラーを`RapReaderError`に変換する。
+///
+/// 展開した観測値の数が格子数と一致しない場合は`GridSizeMismatch`に、
+/// 判別できないランレングス圧縮バイトが見つかった場合は`UnknownRunLengthByte`に、
+/// それ以外の場合は`Decode`に変換する。
+impl From<DecodeError> for RapReaderError {
+    fn from(e: DecodeError) -> Self {
+        match e {
+            DecodeError::CellCountMismatch { expected, actual } => {
+                Self::GridSizeMismatch { expected, actual }
+            }
+            DecodeError::UnknownRunLengthByte {
+                byte,
+                offset,
+                cell_index,
+            } => Self::UnknownRunLengthByte {
+                byte,
+                offset,
+                cell_index,
+            },
+            e => Self::Decode(e),
+        }
+    }
+}
+
+impl RapReaderError {
+    /// RAPファイルの解析エラーを構築する。
+    fn parse(offset: u64, field: &'static str, source: std::io::Error) -> Self {
+        Self::Parse {
+            offset,
+            field,
+            source: Arc::new(source),
+        }
+    }
+}
+
+/// RapReader結果型
+pub type RapReaderResult<T> = Result<T, RapReaderError>;
+
+/// 予備の領域を読み飛ばす。
+///
+/// `reserved_regions`が`Some`の場合は、予備の領域を読み込んで追加する。
+///
+/// # 引数
+///
+/// * `reader` - 予備の領域を読み飛ばすリーダー
+/// * `bytes` - 予備の領域のバイト数
+/// * `name` - 予備の領域の名前
+/// * `reserved_regions` - 読み込んだ予備の領域を追加する`Vec`
+fn skip_reserved_region<R>(
+    reader: &mut R,
+    bytes: usize,
+    name: &'static str,
+    reserved_regions: &mut Option<Vec<ReservedRegion>>,
+) -> RapReaderResult<()>
+where
+    R: Read + Seek,
+{
+    match reserved_regions {
+        Some(regions) => {
+            let position = reader.stream_position()?;
+            let bytes = read_bytes(reader, bytes, name)?;
+            regions.push(ReservedRegion {
+                name,
+                position,
+                bytes,
+            });
+        }
+        None => {
+            reader.seek(SeekFrom::Current(bytes as i64))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// 引数で指定されたバイト数を読み込む。
+///
+/// 読み込むバイト数はファイルに記録されている値であることがあるため、
+/// 事前にバイト数分のメモリを確保せず、読み込んだバイト列の分だけメモリを確保する。
+///
+/// # 引数
+///
+/// * `reader` - バイト列を読み込むリーダー
+/// * `bytes` - 読み込むバイト数
+/// * `field` - 読み込むフィールドの名前
+///
+/// # 戻り値
+///
+/// 読み込んだバイト列
+fn read_bytes<R>(reader: &mut R, bytes: usize, field: &'static str) -> RapReaderResult<Vec<u8>>
+where
+    R: Read + Seek,
+{
+    let mut buf = Vec::new();
+    if let Err(e) = reader.take(bytes as u64).read_to_end(&mut buf) {
+        return Err(parse_error(reader, buf.len(), field, e));
+    }
+    if buf.len() < bytes {
+        return Err(parse_error(
+            reader,
+            buf.len(),
+            field,
+            std::io::Error::from(std::io::ErrorKind::UnexpectedEof),
+        ));
+    }
 
-/// 圧縮方法
-const COMPRESSION_METHOD: u16 = 1; // ラン・レングス符号圧縮
+    Ok(buf)
+}
 
-/// RapReaderエラー型
-#[derive(Debug, Clone, thiserror::Error)]
-pub enum RapReaderError {
-    /// 予期しない例外
-    #[error("{0}")]
-    Unexpected(String),
+/// 引数で指定されたバッファーの大きさだけバイト列を読み込む。
+///
+/// 数値などの固定長のフィールドを読み込むときに使用して、読み込むたびにメモリを確保しない。
+///
+/// # 引数
+///
+/// * `reader` - バイト列を読み込むリーダー
+/// * `buf` - 読み込んだバイト列を格納するバッファー
+/// * `field` - 読み込むフィールドの名前
+fn read_exact<R>(reader: &mut R, buf: &mut [u8], field: &'static str) -> RapReaderResult<()>
+where
+    R: Read + Seek,
+{
+    let mut filled = 0;
+    while filled < buf.len() {
+        match reader.read(&mut buf[filled..]) {
+            Ok(0) => {
+                return Err(parse_error(
+                    reader,
+                    filled,
+                    field,
+                    std::io::Error::from(std::io::ErrorKind::UnexpectedEof),
+                ))
+            }
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(parse_error(reader, filled, field, e)),
+        }
+    }
 
-    /// ファイル・オープン・エラー
-    #[error("ファイルを開くときにエラーが発生しました。{0}")]
-    Open(String),
+    Ok(())
+}
 
-    /// サポートしていない観測時間間隔
-    #[error("サポートしていない時間間隔です。`{0}`")]
-    ObservationIntervalUnsupported(u32),
+/// 読み込みに失敗したフィールドの解析エラーを構築する。
+///
+/// 読み込みに成功したときにシークしないように、フィールドの先頭のオフセットは、
+/// 失敗したときの位置から読み込んだバイト数を引いて求める。
+///
+/// # 引数
+///
+/// * `reader` - フィールドを読み込んだリーダー
+/// * `consumed` - フィールドから読み込んだバイト数
+/// * `field` - 読み込むフィールドの名前
+/// * `source` - 読み込みに失敗した原因
+fn parse_error<R>(
+    reader: &mut R,
+    consumed: usize,
+    field: &'static str,
+    source: std::io::Error,
+) -> RapReaderError
+where
+    R: Seek,
+{
+    let offset = reader
+        .stream_position()
+        .map_or(0, |position| position.saturating_sub(consumed as u64));
 
-    /// サポートしていない地図種別
-    #[error("サポートしていない地図種別です。`{0}`")]
-    MapTypeUnsupported(u16),
+    RapReaderError::parse(offset, field, source)
+}
 
-    /// サポートしていない圧縮方法
-    #[error("サポートしていない圧縮方法です。`{0}`")]
-    CompressionMethodUnsupported(u16),
+/// データ部の圧縮データを読み込む。
+///
+/// 圧縮後の大きさはファイルに記録されている値であるため、圧縮データの末尾がファイルの大きさを超える場合は、
+/// メモリを確保する前にエラーを返す。
+///
+/// # 引数
+///
+/// * `reader` - 圧縮データを読み込むリーダー
+/// * `dp` - 圧縮データを読み込む観測データの属性
+///
+/// # 戻り値
+///
+/// 圧縮データ
+fn read_compressed_data<R>(reader: &mut R, dp: &DataProperty) -> RapReaderResult<Vec<u8>>
+where
+    R: Read + Seek,
+{
+    let file_size = reader.seek(SeekFrom::End(0))?;
+    let end_position = dp.compressed_data_end_position();
+    if file_size < end_position {
+        return Err(RapReaderError::InvalidBlock {
+            datetime: dp.observation_date_time,
+            reason: format!(
+                "圧縮データの末尾(0x{end_position:X})がファイルの大きさ(0x{file_size:X})を超えています。"
+            ),
+        });
+    }
+    reader.seek(SeekFrom::Start(dp.compressed_data_start_position()))?;
 
-    /// 指定された日付のデータが記録されていない
-    #[error("指定された日付のデータは記録されていません。`{0:?}`")]
-    DataDoesNotRecorded(PrimitiveDateTime),
+    read_bytes(reader, dp.compressed_data_size as usize, "圧縮データ")
 }
 
-/// RapReader結果型
-pub type RapReaderResult<T> = Result<T, RapReaderError>;
-
 /// 文字列を読み込む。
 ///
 /// 読み込んだ文字列は、末尾の空白文字をトリムした結果である。
@@ -422,45 +2472,46 @@ pub type RapReaderResult<T> = Result<T, RapReaderError>;
 ///
 /// * `reader` - 文字列を読み込むリーダー
 /// * `bytes` - 読み込むバイト数
+/// * `field` - 読み込むフィールドの名前
 ///
 /// # 戻り値
 ///
 /// 読み込んだ文字列
-fn read_str<R>(reader: &mut R, bytes: usize) -> RapReaderResult<String>
+fn read_str<R>(reader: &mut R, bytes: usize, field: &'static str) -> RapReaderResult<String>
 where
-    R: Read,
+    R: Read + Seek,
 {
-    let mut buf = vec![0u8; bytes];
-    reader.read_exact(&mut buf).map_err(|e| {
-        RapReaderError::Unexpected(format!(
-            "ファイルから{bytes}バイトの読み込みに失敗しました。{e}"
-        ))
-    })?;
-    let s = String::from_utf8(buf).map_err(|e| {
+    let buf = read_bytes(reader, bytes, field)?;
+    let s = std::str::from_utf8(until_nul(&buf)).map_err(|e| {
         RapReaderError::Unexpected(format!(
-            "utf8文字列に変換できないバイト列が記録されています。{e}"
+            "{field}にutf8文字列に変換できないバイト列が記録されています。{e}"
         ))
     })?;
-    let s = s.trim_end().to_string();
 
-    Ok(s)
+    Ok(s.trim_end().to_string())
+}
+
+/// 固定長の文字列フィールドのバイト列から、最初のNULバイトより前のバイト列を返す。
+///
+/// 固定長の文字列フィールドは、NULバイトで埋められていることがあるため、
+/// 最初のNULバイト以降を文字列に含めない。
+fn until_nul(buf: &[u8]) -> &[u8] {
+    match buf.iter().position(|b| *b == 0x00) {
+        Some(position) => &buf[..position],
+        None => buf,
+    }
 }
 
 macro_rules! read_number {
     ($func_name:ident, $type: ty) => {
-        fn $func_name<R>(reader: &mut R) -> RapReaderResult<$type>
+        fn $func_name<R>(reader: &mut R, field: &'static str) -> RapReaderResult<$type>
         where
-            R: Read,
+            R: Read + Seek,
         {
-            let bytes = std::mem::size_of::<$type>();
-            let mut buf = vec![0u8; bytes];
-            reader.read_exact(&mut buf).map_err(|e| {
-                RapReaderError::Unexpected(format!(
-                    "ファイルから{bytes}バイトの読み込みに失敗しました。{e}"
-                ))
-            })?;
+            let mut buf = [0u8; std::mem::size_of::<$type>()];
+            read_exact(reader, &mut buf, field)?;
 
-            Ok(<$type>::from_le_bytes(buf.try_into().unwrap()))
+            Ok(<$type>::from_le_bytes(buf))
         }
     };
 }
@@ -472,59 +2523,39 @@ read_number!(read_u64, u64);
 
 fn read_date_time<R>(reader: &mut R) -> RapReaderResult<PrimitiveDateTime>
 where
-    R: Read,
+    R: Read + Seek,
 {
-    let year = read_u16(reader)
-        .map_err(|e| RapReaderError::Unexpected(format!("観測年の読み込みに失敗しました。{e}")))?;
-    let month = read_u8(reader)
-        .map_err(|e| RapReaderError::Unexpected(format!("観測月の読み込みに失敗しました。{e}")))?;
-    let month_enum = Month::try_from(month).map_err(|e| {
-        RapReaderError::Unexpected(format!(
-            "ファイルに記録されている月({month})が不正です。{e}"
-        ))
-    })?;
-    let day = read_u8(reader)
-        .map_err(|e| RapReaderError::Unexpected(format!("観測日の読み込みに失敗しました。{e}")))?;
-    let hour = read_u8(reader)
-        .map_err(|e| RapReaderError::Unexpected(format!("観測時の読み込みに失敗しました。{e}")))?;
-    let minute = read_u8(reader)
-        .map_err(|e| RapReaderError::Unexpected(format!("観測分の読み込みに失敗しました。{e}")))?;
-    let date = Date::from_calendar_date(year as i32, month_enum, day).map_err(|e| {
-        RapReaderError::Unexpected(format!(
-            "ファイルに記録されている年月日から、日付を構築できませんでした。{e}"
-        ))
-    })?;
-    let time = Time::from_hms(hour, minute, 0).map_err(|e| {
-        RapReaderError::Unexpected(format!(
-            "ファイルに記録されている時分から、時間を構築できませんでした。{e}"
-        ))
-    })?;
+    let year = read_u16(reader, "観測年")?;
+    let month = read_u8(reader, "観測月")?;
+    let month_enum = Month::try_from(month).map_err(|_| RapReaderError::InvalidMonth(month))?;
+    let day = read_u8(reader, "観測日")?;
+    let hour = read_u8(reader, "観測時")?;
+    let minute = read_u8(reader, "観測分")?;
+    let date = Date::from_calendar_date(year as i32, month_enum, day)
+        .map_err(|_| RapReaderError::InvalidDate { year, month, day })?;
+    // 24時00分と記録されている場合は、翌日の0時00分とする
+    if hour == 24 && minute == 0 {
+        let next_date = date
+            .next_day()
+            .ok_or(RapReaderError::InvalidDate { year, month, day })?;
+        return Ok(PrimitiveDateTime::new(next_date, Time::MIDNIGHT));
+    }
+    let time = Time::from_hms(hour, minute, 0)
+        .map_err(|_| RapReaderError::InvalidTime { hour, minute })?;
 
     Ok(PrimitiveDateTime::new(date, time))
 }
 
-fn read_comment_part<R>(reader: &mut R) -> RapReaderResult<CommentPart>
+fn read_comment_part<R>(reader: &mut R, options: &RapReaderOptions) -> RapReaderResult<CommentPart>
 where
     R: Read + Seek,
 {
-    let identifier = read_str(reader, 6).map_err(|e| {
-        RapReaderError::Unexpected(format!("コメントの識別子の読み込みに失敗しました。{e}"))
-    })?;
-    let version = read_str(reader, 5).map_err(|e| {
-        RapReaderError::Unexpected(format!("コメントの版番号の読み込みに失敗しました。{e}"))
-    })?;
-    let comment = read_str(reader, 66).map_err(|e| {
-        RapReaderError::Unexpected(format!(
-            "コメントの作成者コメントの読み込みに失敗しました。{e}"
-        ))
-    })?;
+    let identifier = read_str(reader, 6, "コメントの識別子")?;
+    let version = read_str(reader, 5, "コメントの版番号")?;
+    let comment = read_str(reader, 66, "コメントの作成者コメント")?;
     let mut bytes = [0u8; 3];
-    reader.read_exact(&mut bytes).map_err(|e| {
-        RapReaderError::Unexpected(format!(
-            "コメントの末尾3バイトの読み込みに失敗しました。{e}"
-        ))
-    })?;
-    if bytes != [0x0d, 0x0a, 0x00] {
+    read_exact(reader, &mut bytes, "コメントの末尾3バイト")?;
+    if options.strict_comment_trailer && bytes != [0x0d, 0x0a, 0x00] {
         return Err(RapReaderError::Unexpected(format!(
             "コメントの末尾3バイトが`0x0d 0x0a 0x00`ではありません。実際には{:?}でした。",
             bytes,
@@ -538,72 +2569,30 @@ where
     })
 }
 
-fn read_data_index_part<R>(reader: &mut R) -> RapReaderResult<DataIndexPart>
+/// データ部へのインデックスを、データ部に移動せずに読み込む。
+///
+/// データ部に記録されている圧縮後の大きさ、レーダー運用状況及び解析に使用したアメダスの総数は読み込まない。
+fn read_data_index_entries<R>(
+    reader: &mut R,
+    reserved_regions: &mut Option<Vec<ReservedRegion>>,
+) -> RapReaderResult<DataIndexPart>
 where
     R: Read + Seek,
 {
-    let number_of_data = read_u32(reader).map_err(|e| {
-        RapReaderError::Unexpected(format!(
-            "データ部へのインデックスのデータ数の読み込みに失敗しました。{e}"
-        ))
-    })?;
+    let number_of_data = read_u32(reader, "データ部へのインデックスのデータ数")?;
     let number_of_data = ObservationTimes::try_from(number_of_data)?;
     let mut data_properties = vec![DataProperty::default(); number_of_data as usize];
     for data_property in data_properties.iter_mut() {
         data_property.observation_date_time = read_date_time(reader)?;
-        data_property.observation_element = read_u16(reader).map_err(|e| {
-            RapReaderError::Unexpected(format!(
-                "データ部へのインデックスの要素の読み込みに失敗しました。{e}"
-            ))
-        })?;
-        reader.seek(SeekFrom::Current(8)).map_err(|e| {
-            RapReaderError::Unexpected(format!(
-                "データ部へのインデックスの予備のシークに失敗しました。{e}"
-            ))
-        })?;
-        data_property.data_start_position = read_u32(reader).map_err(|e| {
-            RapReaderError::Unexpected(format!(
-                "データ部へのインデックスのデータの開始位置の読み込みに失敗しました。{e}"
-            ))
-        })?;
-        // データ部に移動してデータ部に記録されている情報を取得
-        let position = reader.stream_position().map_err(|e| {
-            RapReaderError::Unexpected(format!(
-                "データ部へのインデックスのデータの終了位置の取得に失敗しました。{e}"
-            ))
-        })?;
-        reader
-            .seek(SeekFrom::Start(data_property.data_start_position as u64))
-            .map_err(|e| {
-                RapReaderError::Unexpected(format!("データ部の先頭に移動できませんでした。{e}"))
-            })?;
-        data_property.compressed_data_size = read_u32(reader).map_err(|e| {
-            RapReaderError::Unexpected(format!(
-                "データ部の圧縮後の大きさの読み込みに失敗しました。{e}"
-            ))
-        })?;
-        reader
-            .seek(SeekFrom::Current(data_property.compressed_data_size as i64))
-            .map_err(|e| {
-                RapReaderError::Unexpected(format!(
-                    "データ部の圧縮後のデータの末尾に移動できませんでした。{e}"
-                ))
-            })?;
-        data_property.radar_operation_statuses = read_u64(reader).map_err(|e| {
-            RapReaderError::Unexpected(format!(
-                "データ部のレーダー運用状況の読み込みに失敗しました。{e}"
-            ))
-        })?;
-        data_property.number_of_amedas = read_u32(reader).map_err(|e| {
-            RapReaderError::Unexpected(format!(
-                "データ部の解析に使用したアメダスの総数の読み込みに失敗しました。{e}"
-            ))
-        })?;
-        reader.seek(SeekFrom::Start(position)).map_err(|e| {
-            RapReaderError::Unexpected(format!(
-                "データ部へのインデックスのデータの終了位置に移動できませんでした。{e}"
-            ))
-        })?;
+        data_property.observation_element = read_u16(reader, "データ部へのインデックスの要素")?;
+        skip_reserved_region(
+            reader,
+            8,
+            "データ部へのインデックスの予備",
+            reserved_regions,
+        )?;
+        data_property.data_start_position =
+            read_u32(reader, "データ部へのインデックスのデータの開始位置")?;
     }
 
     Ok(DataIndexPart {
@@ -612,52 +2601,42 @@ where
     })
 }
 
-fn read_grid_definition_part<R>(reader: &mut R) -> RapReaderResult<GridDefinitionPart>
+/// データ部に記録されている圧縮後の大きさ、レーダー運用状況及び解析に使用したアメダスの総数を読み込む。
+///
+/// 引数`reader`の読み込み位置が、データ部の先頭になっていることを想定している。
+fn read_data_block_metadata<R>(
+    reader: &mut R,
+    data_property: &mut DataProperty,
+) -> RapReaderResult<()>
 where
     R: Read + Seek,
 {
-    reader.seek(SeekFrom::Current(2)).map_err(|e| {
-        RapReaderError::Unexpected(format!("格子系定義の最初の予備のシークに失敗しました。{e}"))
-    })?;
-    let map_type = read_u16(reader).map_err(|e| {
-        RapReaderError::Unexpected(format!("格子系定義の地図種別の読み込みに失敗しました。{e}"))
-    })?;
-    if map_type != MAP_TYPE {
-        return Err(RapReaderError::MapTypeUnsupported(map_type));
-    }
-    let start_grid_latitude = read_u32(reader).map_err(|e| {
-        RapReaderError::Unexpected(format!(
-            "格子系定義の最初のデータの緯度の読み込みに失敗しました。{e}"
-        ))
-    })?;
-    let start_grid_longitude = read_u32(reader).map_err(|e| {
-        RapReaderError::Unexpected(format!(
-            "格子系定義の最初のデータの経度の読み込みに失敗しました。{e}"
-        ))
-    })?;
-    let grid_width = read_u32(reader).map_err(|e| {
-        RapReaderError::Unexpected(format!("格子系定義の格子の幅の読み込みに失敗しました。{e}"))
-    })?;
-    let grid_height = read_u32(reader).map_err(|e| {
-        RapReaderError::Unexpected(format!(
-            "格子系定義の格子の高さの読み込みに失敗しました。{e}"
-        ))
-    })?;
-    let number_of_h_grids = read_u16(reader).map_err(|e| {
-        RapReaderError::Unexpected(format!(
-            "格子系定義の横方向の格子数の読み込みに失敗しました。{e}"
-        ))
-    })?;
-    let number_of_v_grids = read_u16(reader).map_err(|e| {
-        RapReaderError::Unexpected(format!(
-            "格子系定義の縦方向の格子数の読み込みに失敗しました。{e}"
-        ))
-    })?;
-    reader.seek(SeekFrom::Current(16)).map_err(|e| {
-        RapReaderError::Unexpected(format!("格子系定義の最後の予備のシークに失敗しました。{e}"))
-    })?;
+    data_property.compressed_data_size = read_u32(reader, "データ部の圧縮後の大きさ")?;
+    reader.seek(SeekFrom::Current(data_property.compressed_data_size as i64))?;
+    data_property.radar_operation_statuses = read_u64(reader, "データ部のレーダー運用状況")?;
+    data_property.number_of_amedas = read_u32(reader, "データ部の解析に使用したアメダスの総数")?;
 
-    Ok(GridDefinitionPart {
+    Ok(())
+}
+
+fn read_grid_definition_part<R>(
+    reader: &mut R,
+    reserved_regions: &mut Option<Vec<ReservedRegion>>,
+) -> RapReaderResult<GridDefinition>
+where
+    R: Read + Seek,
+{
+    skip_reserved_region(reader, 2, "格子系定義の最初の予備", reserved_regions)?;
+    let map_type = read_u16(reader, "格子系定義の地図種別")?;
+    let start_grid_latitude = read_u32(reader, "格子系定義の最初のデータの緯度")?;
+    let start_grid_longitude = read_u32(reader, "格子系定義の最初のデータの経度")?;
+    let grid_width = read_u32(reader, "格子系定義の格子の幅")?;
+    let grid_height = read_u32(reader, "格子系定義の格子の高さ")?;
+    let number_of_h_grids = read_u16(reader, "格子系定義の横方向の格子数")?;
+    let number_of_v_grids = read_u16(reader, "格子系定義の縦方向の格子数")?;
+    skip_reserved_region(reader, 16, "格子系定義の最後の予備", reserved_regions)?;
+
+    let grid_definition = GridDefinition {
         map_type,
         start_grid_latitude,
         start_grid_longitude,
@@ -665,35 +2644,81 @@ where
         grid_height,
         number_of_h_grids,
         number_of_v_grids,
-    })
+    };
+    validate_grid_range(&grid_definition)?;
+
+    Ok(grid_definition)
+}
+
+/// 格子系定義の観測範囲が、有効な緯度と経度の範囲に収まっていることを確認する。
+///
+/// 緯度と経度は符号なしの10e-6度単位で記録されているため、格子の中心の緯度は0度から90度、
+/// 経度は0度から180度の範囲に収まっている必要がある。
+fn validate_grid_range(gd: &GridDefinition) -> RapReaderResult<()> {
+    let out_of_range = |reason: String| RapReaderError::GridOutOfRange {
+        grid_definition: *gd,
+        reason,
+    };
+    let v_span = gd.grid_height as u64 * (gd.number_of_v_grids as u64).saturating_sub(1);
+    let h_span = gd.grid_width as u64 * (gd.number_of_h_grids as u64).saturating_sub(1);
+
+    // 最北端の格子の中心の緯度
+    if MAX_LATITUDE < gd.start_grid_latitude as u64 {
+        return Err(out_of_range(format!(
+            "最北端の格子の中心の緯度({})が90度を超えています。",
+            to_degrees(gd.start_grid_latitude)
+        )));
+    }
+    // 最南端の格子の中心の緯度
+    if (gd.start_grid_latitude as u64) < v_span {
+        return Err(out_of_range(format!(
+            "最南端の格子の中心の緯度({})が0度未満です。",
+            (gd.start_grid_latitude as f64 - v_span as f64) / 1_000_000.0
+        )));
+    }
+    // 最東端の格子の中心の経度
+    let max_longitude = gd.start_grid_longitude as u64 + h_span;
+    if MAX_LONGITUDE < max_longitude {
+        return Err(out_of_range(format!(
+            "最東端の格子の中心の経度({})が180度を超えています。",
+            max_longitude as f64 / 1_000_000.0
+        )));
+    }
+
+    Ok(())
 }
 
-fn read_compression_part<R>(reader: &mut R) -> RapReaderResult<CompressionPart>
+fn read_compression_part<R>(
+    reader: &mut R,
+    options: &RapReaderOptions,
+) -> RapReaderResult<CompressionPart>
 where
-    R: Read,
+    R: Read + Seek,
 {
-    let compression_method = read_u16(reader).map_err(|e| {
-        RapReaderError::Unexpected(format!(
-            "圧縮方法・観測値表の圧縮方法の読み込みに失敗しました。{e}"
-        ))
-    })?;
-    if compression_method != COMPRESSION_METHOD {
-        return Err(RapReaderError::CompressionMethodUnsupported(
-            compression_method,
-        ));
-    }
-    let number_of_levels = read_u16(reader).map_err(|e| {
-        RapReaderError::Unexpected(format!(
-            "圧縮方法・観測値表のレベル数の読み込みに失敗しました。{e}"
-        ))
-    })?;
+    let compression_method = read_u16(reader, "圧縮方法・観測値表の圧縮方法")?;
+    let number_of_levels = read_u16(reader, "圧縮方法・観測値表のレベル数")?;
     let mut value_by_levels = vec![0u16; number_of_levels as usize];
     for prep in value_by_levels.iter_mut() {
-        *prep = read_u16(reader).map_err(|e| {
-            RapReaderError::Unexpected(format!(
-                "圧縮方法・観測値表のレベルごとの観測値の読み込みに失敗しました。{e}"
-            ))
-        })?;
+        *prep = read_u16(reader, "圧縮方法・観測値表のレベルごとの観測値")?;
+    }
+
+    // 観測値として妥当な最大値を超えるレベルの観測値を、欠測値に置き換えるかエラーとする
+    if let Some(max) = options.max_plausible_value {
+        for (level, value) in value_by_levels.iter_mut().enumerate() {
+            if is_missing(*value) || *value <= max {
+                continue;
+            }
+            match options.implausible_value_policy {
+                ImplausibleValuePolicy::Missing => *value = MISSING_VALUE,
+                ImplausibleValuePolicy::Error => {
+                    return Err(RapReaderError::ImplausibleValue {
+                        level,
+                        value: *value,
+                        max,
+                    })
+                }
+            }
+        }
     }
 
     Ok(CompressionPart {
@@ -705,13 +2730,9 @@ where
 
 fn read_level_repetitions_part<R>(reader: &mut R) -> RapReaderResult<LevelRepetitionsPart>
 where
-    R: Read,
+    R: Read + Seek,
 {
-    let number_of_level_repetitions = read_u16(reader).map_err(|e| {
-        RapReaderError::Unexpected(format!(
-            "レベル・反復表の表の大きさの読み込みに失敗しました。{e}"
-        ))
-    })?;
+    let number_of_level_repetitions = read_u16(reader, "レベル・反復表の表の大きさ")?;
     let mut level_repetitions = vec![
         LevelRepetition {
             level: 0,
@@ -720,16 +2741,8 @@ where
         number_of_level_repetitions as usize
     ];
     for lr in level_repetitions.iter_mut() {
-        lr.level = read_u8(reader).map_err(|e| {
-            RapReaderError::Unexpected(format!(
-                "レベル・反復表のレベルの読み込みに失敗しました。{e}"
-            ))
-        })?;
-        lr.repetition = read_u8(reader).map_err(|e| {
-            RapReaderError::Unexpected(format!(
-                "レベル・反復表の反復数の読み込みに失敗しました。{e}"
-            ))
-        })?;
+        lr.level = read_u8(reader, "レベル・反復表のレベル")?;
+        lr.repetition = read_u8(reader, "レベル・反復表の反復数")?;
     }
 
     Ok(LevelRepetitionsPart {
@@ -742,17 +2755,19 @@ where
 ///
 /// ライフタイム`'a`は、`RapReader`よりも短命なライフタイムを示す。
 pub struct RapValueIterator<'a> {
-    /// ファイルリーダー
-    reader: FileReader,
+    /// RAPファイル・リーダー
+    reader: RapDataReader,
 
     /// 圧縮データ全体のバイト数
     compressed_data_bytes: usize,
 
-    /// 経度の最小値（10e-6度単位）
+    /// 最西端の格子の中心の経度（10e-6度単位）
     min_longitude: u32,
 
     /// 経度方向の格子数
     number_of_h_grids: u16,
+    /// 緯度方向の格子数（`RapValueIterator::new`で構築した場合は不明）
+    number_of_v_grids: Option<u16>,
 
     /// 格子の高さ（10e-6度単位）
     grid_height: u32,
@@ -766,37 +2781,51 @@ pub struct RapValueIterator<'a> {
 
     /// 圧縮データを読み込んだバイト数
     read_bytes: usize,
-    /// 現在の緯度（10e-6度単位）
-    current_latitude: u32,
-    /// 現在の経度（10e-6度単位）
-    current_longitude: u32,
+    /// 現在の格子の中心の緯度（10e-6度単位）
+    ///
+    /// 緯度が0度未満になった場合は`None`である。
+    current_latitude: Option<u32>,
+    /// 現在の格子の中心の経度（10e-6度単位）
+    ///
+    /// 経度が10e-6度単位の`u32`で表現できる範囲を超えた場合は`None`である。
+    current_longitude: Option<u32>,
     /// 経度方向に格子を移動した回数
     h_moved_times: u16,
+    /// 次に返す格子の、最北西端の格子からの記録順のインデックス
+    cell_index: usize,
     /// 現在の観測値
     current_value: Option<u16>,
+    /// 現在の観測値のレベル
+    current_level: u8,
     /// 現在の観測値を繰り返す回数
     number_of_repetitions: u16,
+    /// イテレーターを破棄するときにRAPファイル・リーダーを戻す場所
+    shared_reader: Option<&'a Mutex<Option<RapDataReader>>>,
+    /// 返す座標の格子内の位置
+    anchor: CoordinateAnchor,
 }
 
 impl<'a> RapValueIterator<'a> {
     /// 観測値を走査して返すイテレーターを構築する。
     ///
     /// 引数`reader`が示すRAPファイル・リーダーの読み込み位置が、圧縮データの先頭位置になっていることを想定している。
+    /// 緯度方向の格子数を指定しないため、`number_of_cells`は`None`を返す。
+    /// 格子系定義から構築する場合は、`RapValueIterator::from_grid_definition`を使用する。
     ///
     /// # 引数
     ///
     /// * `reader` - RAPファイル・リーダー
     /// * `compressed_data_bytes` - 圧縮データ全体のバイト数
-    /// * `max_latitude` - 観測範囲の最北西端の緯度（10e-6度単位）
-    /// * `min_longitude` - 観測範囲の最北西端の経度（10e-6度単位）
-    /// * `number_of_h_grids` - 観測範囲の緯度方向の格子数
+    /// * `max_latitude` - 観測範囲の最北西端の格子の中心の緯度（10e-6度単位）
+    /// * `min_longitude` - 観測範囲の最北西端の格子の中心の経度（10e-6度単位）
+    /// * `number_of_h_grids` - 観測範囲の経度方向の格子数
     /// * `grid_height` - 格子の高さ（10e-6度単位）
     /// * `grid_width` - 格子の幅（10e-6度単位）
     /// * `value_by_levels` - レベルごとの観測値
     /// * `level_repetitions` - レベルと反復数の組み合わせ
     #[allow(clippy::too_many_arguments)]
-    pub fn new(
-        reader: FileReader,
+    pub fn new<R>(
+        reader: R,
         compressed_data_bytes: usize,
         max_latitude: u32,
         min_longitude: u32,
@@ -805,88 +2834,396 @@ impl<'a> RapValueIterator<'a> {
         grid_width: u32,
         value_by_levels: &'a [u16],
         level_repetitions: &'a [LevelRepetition],
-    ) -> Self {
+    ) -> Self
+    where
+        R: Into<RapDataReader>,
+    {
         Self {
-            reader,
+            reader: reader.into(),
             compressed_data_bytes,
             min_longitude,
             number_of_h_grids,
+            number_of_v_grids: None,
             grid_height,
             grid_width,
             value_by_levels,
             level_repetitions,
             read_bytes: 0,
-            current_latitude: max_latitude,
-            current_longitude: min_longitude,
+            current_latitude: Some(max_latitude),
+            current_longitude: Some(min_longitude),
+            h_moved_times: 0,
+            cell_index: 0,
+            current_value: None,
+            current_level: 0,
+            number_of_repetitions: 0,
+            shared_reader: None,
+            anchor: CoordinateAnchor::Center,
+        }
+    }
+
+    /// 格子系定義に従って観測値を走査して返すイテレーターを構築する。
+    ///
+    /// 引数`reader`が示すRAPファイル・リーダーの読み込み位置が、圧縮データの先頭位置になっていることを想定している。
+    ///
+    /// # 引数
+    ///
+    /// * `reader` - RAPファイル・リーダー
+    /// * `compressed_data_bytes` - 圧縮データ全体のバイト数
+    /// * `grid_definition` - 格子系定義
+    /// * `value_by_levels` - レベルごとの観測値
+    /// * `level_repetitions` - レベルと反復数の組み合わせ
+    pub fn from_grid_definition<R>(
+        reader: R,
+        compressed_data_bytes: usize,
+        grid_definition: &GridDefinition,
+        value_by_levels: &'a [u16],
+        level_repetitions: &'a [LevelRepetition],
+    ) -> Self
+    where
+        R: Into<RapDataReader>,
+    {
+        Self {
+            reader: reader.into(),
+            compressed_data_bytes,
+            min_longitude: grid_definition.start_grid_longitude,
+            number_of_h_grids: grid_definition.number_of_h_grids,
+            number_of_v_grids: Some(grid_definition.number_of_v_grids),
+            grid_height: grid_definition.grid_height,
+            grid_width: grid_definition.grid_width,
+            value_by_levels,
+            level_repetitions,
+            read_bytes: 0,
+            current_latitude: Some(grid_definition.start_grid_latitude),
+            current_longitude: Some(grid_definition.start_grid_longitude),
             h_moved_times: 0,
+            cell_index: 0,
             current_value: None,
+            current_level: 0,
             number_of_repetitions: 0,
+            shared_reader: None,
+            anchor: CoordinateAnchor::Center,
+        }
+    }
+
+    /// イテレーターが返す座標の、格子内の位置を指定する。
+    ///
+    /// 既定では、格子の中心の座標を返す。
+    ///
+    /// # 引数
+    ///
+    /// * `anchor` - 返す座標の格子内の位置
+    ///
+    /// # 戻り値
+    ///
+    /// 指定した位置の座標を返すイテレーター
+    pub fn with_anchor(mut self, anchor: CoordinateAnchor) -> Self {
+        self.anchor = anchor;
+        self
+    }
+
+    /// 格子の幅と高さを度単位で返す。
+    fn grid_size_deg(&self) -> (f64, f64) {
+        (to_degrees(self.grid_width), to_degrees(self.grid_height))
+    }
+
+    /// イテレーターが返す観測値の総数を返す。
+    ///
+    /// `RapValueIterator::new`で構築した場合は、緯度方向の格子数が不明なため`None`を返す。
+    pub fn number_of_cells(&self) -> Option<usize> {
+        self.number_of_v_grids
+            .map(|v| self.number_of_h_grids as usize * v as usize)
+    }
+
+    /// イテレーターが、格子数と同じ数の観測値を返して終了したか確認する。
+    ///
+    /// 圧縮データが途中で終了している場合、イテレーターは格子数より少ない観測値を返して終了するため、
+    /// 走査を終えた後にこのメソッドで確認する。
+    /// `RapValueIterator::new`で構築した場合は、緯度方向の格子数が不明なため、
+    /// すべての圧縮データを展開したことのみを確認する。
+    ///
+    /// # 戻り値
+    ///
+    /// すべての圧縮データを展開して、返した観測値の数が格子数と一致する場合は`true`
+    pub fn finished_cleanly(&self) -> bool {
+        if self.number_of_repetitions != 0 || self.read_bytes < self.compressed_data_bytes {
+            return false;
         }
+
+        self.number_of_cells()
+            .is_none_or(|number_of_cells| number_of_cells == self.cell_index)
+    }
+
+    /// 観測値に、格子の緯度方向と経度方向のインデックスを付けて返すイテレーターを返す。
+    ///
+    /// インデックスは、緯度と経度から計算せずに、イテレーターが格子を移動した回数から求める。
+    ///
+    /// # 戻り値
+    ///
+    /// `(北からの行のインデックス, 西からの列のインデックス, 座標と観測値)`を返すイテレーター
+    pub fn enumerate_grid(self) -> EnumerateGrid<'a> {
+        EnumerateGrid { iterator: self }
     }
 
     /// ランレングス圧縮バイトを読み込み。
     fn read_run_length_byte(&mut self) -> RapReaderResult<u8> {
         let mut buf = [0u8; 1];
-        self.reader.read_exact(&mut buf).map_err(|e| {
-            RapReaderError::Unexpected(format!("データ部の読み込みに失敗しました。{e}"))
-        })?;
+        self.reader
+            .read_exact(&mut buf)
+            .map_err(|e| match self.reader.stream_position() {
+                Ok(offset) => RapReaderError::parse(offset, "データ部の圧縮データ", e),
+                Err(_) => RapReaderError::from(e),
+            })?;
         self.read_bytes += 1;
 
-        Ok(buf[0])
+        Ok(buf[0])
+    }
+
+    /// 圧縮された測定値を読み込む。
+    fn expand_run_length(&mut self) -> RapReaderResult<ExpandedValue> {
+        let (offset, cell_index) = (self.read_bytes, self.cell_index);
+        let (value_by_levels, level_repetitions) = (self.value_by_levels, self.level_repetitions);
+        let mut read_error = None;
+        let next_byte = || {
+            self.read_run_length_byte().map_err(|e| {
+                read_error = Some(e);
+                DecodeError::UnexpectedEnd {
+                    offset: self.read_bytes,
+                }
+            })
+        };
+        let result = decode::expand_run_length(
+            offset,
+            cell_index,
+            next_byte,
+            value_by_levels,
+            level_repetitions,
+        );
+
+        match read_error {
+            Some(e) => Err(e),
+            None => result.map_err(RapReaderError::from),
+        }
+    }
+
+    /// 格子に展開せずに、ランレングス圧縮された観測値を1つずつ返す。
+    fn next_run(&mut self) -> Option<RapReaderResult<ExpandedValue>> {
+        if self.compressed_data_bytes <= self.read_bytes {
+            return None;
+        }
+        let ev = self.expand_run_length();
+        if let Ok(ev) = &ev {
+            self.cell_index += ev.number_of_repetitions as usize;
+        }
+
+        Some(ev)
     }
 
-    /// 圧縮された測定値を読み込む。
-    fn expand_run_length(&mut self) -> RapReaderResult<ExpandedValue> {
-        // 1バイト読み込み
-        let buf = self.read_run_length_byte()?;
-        let expanded_value = if buf & 0x80 == 0x00 {
-            // レベル反復表によるランレングス圧縮(a)
-            let lr = self.level_repetitions[buf as usize];
-            ExpandedValue {
-                value: self.value_by_levels[lr.level as usize],
-                number_of_repetitions: lr.repetition as u16 + 2,
-            }
-        } else if buf & 0xE0 == 0xC0 {
-            // レベル反復表によらないランレングス圧縮(b)
-            let value = self.value_by_levels[(buf & 0x1F) as usize];
-            let number_of_repetitions = self.read_run_length_byte()? as u16 + 2;
-            ExpandedValue {
-                value,
-                number_of_repetitions,
+    /// 観測値を返さずに、引数で指定された格子の直前まで走査する。
+    ///
+    /// `LocationValue`を構築せずに、ランレングス圧縮された観測値の反復数と現在の座標のみを進める。
+    /// このメソッドを呼び出した後に`next`を呼び出すと、`target`番目の格子の観測値を返す。
+    /// 圧縮データが`target`番目の格子より前で終了している場合は、圧縮データの終わりまで走査する。
+    /// 緯度が0度未満になる格子がある場合は、その格子の直前で走査を終了し、
+    /// 次の`next`が`LatitudeUnderflow`エラーを返す。
+    /// 同様に、経度が表現できる範囲を超える格子がある場合は、その格子の直前で走査を終了し、
+    /// 次の`next`が`GridSizeMismatch`エラーを返す。
+    ///
+    /// # 引数
+    ///
+    /// * `target` - 次に返す格子の、最北西端の格子からの記録順のインデックス
+    pub fn skip_to_cell(&mut self, target: usize) -> RapReaderResult<()> {
+        if target < self.cell_index {
+            return Err(RapReaderError::SkipBackward {
+                target,
+                cell_index: self.cell_index,
+            });
+        }
+
+        while self.cell_index < target {
+            let (Some(current_latitude), Some(current_longitude)) =
+                (self.current_latitude, self.current_longitude)
+            else {
+                break;
+            };
+
+            // 現在の観測値の繰り返し回数が0の場合、圧縮データを読み込み
+            if self.number_of_repetitions == 0 {
+                if self.compressed_data_bytes <= self.read_bytes {
+                    break;
+                }
+                let ev = self.expand_run_length()?;
+                self.current_level = ev.level;
+                self.current_value = if is_missing(ev.value) {
+                    None
+                } else {
+                    Some(ev.value)
+                };
+                self.number_of_repetitions = ev.number_of_repetitions;
             }
-        } else if buf & 0xC0 == 0x80 {
-            // 頻度が多い単独のレベル値(c)
-            let value = self.value_by_levels[(buf & 0x3F) as usize];
-            ExpandedValue {
-                value,
-                number_of_repetitions: 1,
+
+            // 現在の観測値の反復数、目的の格子まで及び現在の行の最東端までの格子数のうち、最も少ない数だけ移動
+            let row_remaining = self
+                .number_of_h_grids
+                .saturating_sub(self.h_moved_times)
+                .max(1);
+            let mut n = (self.number_of_repetitions as usize)
+                .min(target - self.cell_index)
+                .min(row_remaining as usize) as u16;
+
+            // 行の最東端の格子から移動する場合は経度を加算しないため、経度を加算する回数は移動する格子数より1少ない
+            let reaches_row_end = self.number_of_h_grids <= self.h_moved_times + n;
+            let additions = if reaches_row_end { n - 1 } else { n } as u32;
+            let max_additions = (u32::MAX - current_longitude)
+                .checked_div(self.grid_width)
+                .unwrap_or(u32::MAX);
+            if max_additions < additions {
+                // 経度が表現できる範囲を超える格子の直前まで移動
+                n = max_additions as u16 + 1;
+                self.cell_index += n as usize;
+                self.current_longitude = None;
+                self.h_moved_times += n;
+                self.number_of_repetitions -= n;
+                break;
             }
-        } else if buf == 0xFE {
-            // 頻度が少ない単独のレベル値(d)
-            let level = self.read_run_length_byte()? as usize;
-            ExpandedValue {
-                value: self.value_by_levels[level],
-                number_of_repetitions: 1,
+
+            self.cell_index += n as usize;
+            self.current_longitude = Some(current_longitude + self.grid_width * additions);
+            self.h_moved_times += n;
+            if reaches_row_end {
+                self.current_latitude = current_latitude.checked_sub(self.grid_height);
+                self.current_longitude = Some(self.min_longitude);
+                self.h_moved_times = 0;
             }
-        } else {
-            return Err(RapReaderError::Unexpected(format!(
-                "データ部に判別できないバイトが見つかりました。`0x{buf:x}"
-            )));
-        };
+            self.number_of_repetitions -= n;
+        }
+
+        Ok(())
+    }
+}
+
+impl Drop for RapValueIterator<'_> {
+    fn drop(&mut self) {
+        if let Some(shared_reader) = self.shared_reader {
+            let reader = std::mem::replace(
+                &mut self.reader,
+                RapDataReader::Bytes(Cursor::new(Arc::from([]))),
+            );
+            *shared_reader.lock().unwrap_or_else(|e| e.into_inner()) = Some(reader);
+        }
+    }
+}
+
+/// 観測値に、格子の緯度方向と経度方向のインデックスを付けて返すイテレーター
+///
+/// `RapValueIterator::enumerate_grid`で構築する。
+pub struct EnumerateGrid<'a> {
+    /// 観測値を走査するイテレーター
+    iterator: RapValueIterator<'a>,
+}
+
+impl Iterator for EnumerateGrid<'_> {
+    type Item = RapReaderResult<(u16, u16, LocationValue)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let row = self
+            .iterator
+            .cell_index
+            .checked_div(self.iterator.number_of_h_grids as usize)
+            .unwrap_or(0) as u16;
+        let col = self.iterator.h_moved_times;
+
+        Some(self.iterator.next()?.map(|lv| (row, col, lv)))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iterator.size_hint()
+    }
+}
+
+/// 座標が示す格子内の位置
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum CoordinateAnchor {
+    /// 格子の中心
+    #[default]
+    Center,
+    /// 格子の北西端
+    ///
+    /// 格子の中心から、経度方向に格子の幅の半分だけ西、緯度方向に格子の高さの半分だけ北の位置である。
+    NorthWestCorner,
+}
+
+impl CoordinateAnchor {
+    /// 格子の中心の座標を、この位置の座標に変換する。
+    fn shift_from_center(
+        self,
+        longitude: f64,
+        latitude: f64,
+        width: f64,
+        height: f64,
+    ) -> (f64, f64) {
+        match self {
+            Self::Center => (longitude, latitude),
+            Self::NorthWestCorner => (longitude - width / 2.0, latitude + height / 2.0),
+        }
+    }
+
+    /// この位置の座標を、格子の中心の座標に変換する。
+    fn shift_to_center(self, longitude: f64, latitude: f64, width: f64, height: f64) -> (f64, f64) {
+        match self {
+            Self::Center => (longitude, latitude),
+            Self::NorthWestCorner => (longitude + width / 2.0, latitude - height / 2.0),
+        }
+    }
+}
+
+/// 記録順に格納した観測値の並び順の原点
+///
+/// RAPファイルは、最北西端の格子から北から南に向かって観測値を記録している。
+/// 南から北に向かって行を並べる配列を扱うライブラリに観測値を渡す場合は、`Origin::BottomLeft`を指定する。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Origin {
+    /// 最北西端の格子から、北から南に向かって行を並べる（RAPファイルの記録順）
+    #[default]
+    TopLeft,
+    /// 最南西端の格子から、南から北に向かって行を並べる
+    BottomLeft,
+}
 
-        Ok(expanded_value)
+impl Origin {
+    /// 最北西端の格子から記録順に格納した観測値を、この原点からの並び順に並べ替える。
+    ///
+    /// 行内の格子は、どちらの原点でも西から東に並べる。
+    fn reorder<T>(self, values: &mut [T], number_of_h_grids: usize) {
+        if self == Self::TopLeft || number_of_h_grids == 0 {
+            return;
+        }
+        // 全体を反転した後に行ごとに反転して、行の順番のみを反転する
+        values.reverse();
+        for row in values.chunks_mut(number_of_h_grids) {
+            row.reverse();
+        }
     }
 }
 
 /// 座標と観測値
+///
+/// 緯度と経度は、格子の中心の座標である。
+/// RAPファイルの格子系定義に記録されている最初の緯度と経度は、最北西端の格子の中心を示すため、
+/// 最初の観測値の座標は、格子系定義の最初の緯度と経度に一致する。
+/// 格子の範囲は、中心から経度方向と緯度方向に、それぞれ格子の幅と高さの半分だけ広がる。
+/// `RapValueIterator::with_anchor`で格子の北西端を指定した場合は、格子の北西端の座標である。
+///
+/// 観測値が0の格子（雨量0mm）と欠測値の格子は区別される。
+/// 観測値が0の格子は`Some(0)`、欠測値の格子は`None`である。
+/// CSVファイルなどに出力する場合、欠測値は`ExportOptions::missing`で指定した文字列で出力される。
 pub struct LocationValue {
-    /// 緯度（度）
+    /// 格子の中心の緯度（度）
     pub latitude: f64,
-    /// 経度（度）
+    /// 格子の中心の経度（度）
     pub longitude: f64,
     /// 観測値
     ///
-    /// 欠測値は`None`を返す。
+    /// 欠測値は`None`を返す。観測値が0の場合は`Some(0)`であり、欠測値と区別される。
     pub value: Option<u16>,
 }
 
@@ -899,35 +3236,63 @@ impl<'a> Iterator for RapValueIterator<'a> {
             return None;
         }
 
+        // 格子の緯度が0度未満になった場合は、エラーを返して終了
+        let Some(current_latitude) = self.current_latitude else {
+            self.number_of_repetitions = 0;
+            self.read_bytes = self.compressed_data_bytes;
+            return Some(Err(RapReaderError::LatitudeUnderflow {
+                cell_index: self.cell_index,
+            }));
+        };
+        // 格子の経度が表現できる範囲を超えた場合は、エラーを返して終了
+        let Some(current_longitude) = self.current_longitude else {
+            self.number_of_repetitions = 0;
+            self.read_bytes = self.compressed_data_bytes;
+            return Some(Err(RapReaderError::GridSizeMismatch {
+                expected: self.number_of_cells().unwrap_or(self.cell_index),
+                actual: self.cell_index,
+            }));
+        };
+
         // 現在の観測値の繰り返し回数が0の場合、圧縮データを読み込み
         if self.number_of_repetitions == 0 {
             let ev = match self.expand_run_length() {
                 Ok(ev) => ev,
                 Err(e) => return Some(Err(e)),
             };
-            self.current_value = if ev.value < u16::MAX {
-                Some(ev.value)
-            } else {
+            self.current_level = ev.level;
+            self.current_value = if is_missing(ev.value) {
                 None
+            } else {
+                Some(ev.value)
             };
             self.number_of_repetitions = ev.number_of_repetitions;
         }
 
         // 結果を生成
+        let (width, height) = self.grid_size_deg();
+        let (longitude, latitude) = self.anchor.shift_from_center(
+            to_degrees(current_longitude),
+            to_degrees(current_latitude),
+            width,
+            height,
+        );
         let result = Some(Ok(LocationValue {
-            latitude: self.current_latitude as f64 / 1_000_000.0,
-            longitude: self.current_longitude as f64 / 1_000_000.0,
+            latitude,
+            longitude,
             value: self.current_value,
         }));
 
         // 格子を移動
-        self.current_longitude += self.grid_width;
+        self.cell_index += 1;
         self.h_moved_times += 1;
         // 経度方向の格子の数だけ緯度方向に移動した場合、現在の格子より1つ南で、最西端の格子に移動
         if self.number_of_h_grids <= self.h_moved_times {
-            self.current_latitude -= self.grid_height;
-            self.current_longitude = self.min_longitude;
+            self.current_latitude = current_latitude.checked_sub(self.grid_height);
+            self.current_longitude = Some(self.min_longitude);
             self.h_moved_times = 0;
+        } else {
+            self.current_longitude = current_longitude.checked_add(self.grid_width);
         }
 
         // 現在の観測値を繰り返す回数を減らす
@@ -935,13 +3300,20 @@ impl<'a> Iterator for RapValueIterator<'a> {
 
         result
     }
-}
 
-struct ExpandedValue {
-    /// 観測値
-    value: u16,
-    /// 観測値を返却する回数
-    number_of_repetitions: u16,
+    /// 残りの格子数を返す。
+    ///
+    /// `RapValueIterator::new`で構築した場合は、格子数が不明なため`(0, None)`を返す。
+    /// 格子数が不明な場合があるため、`ExactSizeIterator`は実装しない。
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.number_of_cells() {
+            Some(number_of_cells) => {
+                let remaining = number_of_cells.saturating_sub(self.cell_index);
+                (remaining, Some(remaining))
+            }
+            None => (0, None),
+        }
+    }
 }
 
 #[rustfmt::skip]
@@ -958,17 +3330,17 @@ where
     writeln!(writer, "    作成者コメント: {}", reader.creator_comment())?;
     writeln!(writer, "管理部 - データ部へのインデックス")?;
     writeln!(writer, "    データ数: {}", reader.number_of_data())?;
-    print_data_properties(writer, reader.data_properties())?;
+    print_data_properties(writer, &reader.resolved_data_properties())?;
     writeln!(writer, "管理部 - 格子系定義")?;
-    writeln!(writer, "    地図種別: {}", reader.map_type())?;
-    writeln!(writer, "    最北西端の緯度: {}", reader.grid_start_latitude())?;
-    writeln!(writer, "    最北西端の経度: {}", reader.grid_start_longitude())?;
+    writeln!(writer, "    地図種別: {} ({})", reader.map_type_name(), reader.map_type())?;
+    writeln!(writer, "    最北西端の格子の中心の緯度: {}", reader.grid_start_latitude())?;
+    writeln!(writer, "    最北西端の格子の中心の経度: {}", reader.grid_start_longitude())?;
     writeln!(writer, "    格子の幅: {}", reader.grid_width())?;
     writeln!(writer, "    格子の高さ: {}", reader.grid_height())?;
     writeln!(writer, "    経度方向の格子数: {}", reader.number_of_h_grids())?;
     writeln!(writer, "    緯度方向の格子数: {}", reader.number_of_v_grids())?;
     writeln!(writer, "管理部 - 圧縮方法、観測値表")?;
-    writeln!(writer, "    圧縮方法: {}", reader.compression_method())?;
+    writeln!(writer, "    圧縮方法: {} ({})", reader.compression_method_name(), reader.compression_method())?;
     writeln!(writer, "    レベルの数: {}", reader.number_of_levels())?;
     print_value_by_levels(writer, reader.value_by_levels())?;
     writeln!(writer, "    レベルと反復数の数: {}", reader.number_of_level_repetitions())?;
@@ -986,12 +3358,13 @@ where
     W: Write,
 {
     writeln!(writer, "    記録されている観測データ")?;
-    writeln!(writer, "    date-time               elem   start-pos")?;
-    writeln!(writer, "    ----------------------------------------")?;
+    writeln!(writer, "    date-time               elem   start-pos  element")?;
+    writeln!(writer, "    ---------------------------------------------------")?;
     for dp in data_properties {
         let dt_str = dp.observation_date_time.format(DATETIME_FMT).unwrap();
         let pos_str = format!("0x{:X}", dp.data_start_position);
-        writeln!(writer, "    {:<20}{:>8}{:>12}", dt_str, dp.observation_element, pos_str)?;
+        let element = dp.observation_element_typed();
+        writeln!(writer, "    {:<20}{:>8}{:>12}  {}", dt_str, dp.observation_element, pos_str, element)?;
     }
 
     Ok(())
@@ -1005,10 +3378,10 @@ where
     writeln!(writer, "    level       value")?;
     writeln!(writer, "    -----------------")?;
     for (level, value) in value_by_levels.iter().enumerate() {
-        let value = if value < &u16::MAX {
-            value.to_string()
-        } else {
+        let value = if is_missing(*value) {
             String::from("None")
+        } else {
+            value.to_string()
         };
         writeln!(writer, "{:>9}{:>12}", level, value)?;
     }
@@ -1059,6 +3432,148 @@ where
     Ok(())
 }
 
+/// 観測値を出力するときのオプション
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExportOptions {
+    /// 出力する観測値の最小値
+    ///
+    /// 観測値がこの値未満の格子を出力しない。欠測値の格子は、この値にかかわらず出力する。
+    /// `None`の場合は、すべての格子を出力する。
+    pub min_value: Option<u16>,
+
+    /// CSVファイルに出力する緯度と経度の小数点以下の桁数
+    ///
+    /// 格子の中心の座標と、格子を表現するWKTの座標に適用する。
+    /// RAPファイルは緯度と経度を10e-6度単位で記録しているため、既定値は6である。
+    pub precision: usize,
+
+    /// 出力する緯度と経度の、格子内の位置
+    ///
+    /// 格子を表現するWKTには影響しない。既定値は格子の中心である。
+    pub anchor: CoordinateAnchor,
+
+    /// 出力する観測データの属性
+    ///
+    /// `Some`の場合、観測日時、レーダー運用状況及び解析に使用したアメダスの総数を、
+    /// CSVファイルには先頭の`#`で始まるコメント行として、Parquetファイルにはキー・バリュー・メタデータとして出力する。
+    /// `RapReader::data_property`メソッドで取得した属性を指定する。既定値は`None`である。
+    pub metadata: Option<DataProperty>,
+
+    /// CSVファイルに出力する欠測値の文字列
+    ///
+    /// 観測値が0の格子は`0`を出力するため、欠測値と区別できる文字列を指定する。
+    /// Parquetファイルには影響せず、欠測値は常に`NULL`で記録する。既定値は空文字列である。
+    /// コマンドライン引数など、実行時に決まる文字列も`String`から指定できる。
+    pub missing: Cow<'static, str>,
+}
+
+impl Default for ExportOptions {
+    fn default() -> Self {
+        Self {
+            min_value: None,
+            precision: DEFAULT_PRECISION,
+            anchor: CoordinateAnchor::Center,
+            metadata: None,
+            missing: Cow::Borrowed(""),
+        }
+    }
+}
+
+impl ExportOptions {
+    /// 観測値が0の格子を出力しないオプションを返す。
+    pub fn skip_zero() -> Self {
+        Self {
+            min_value: Some(1),
+            ..Default::default()
+        }
+    }
+
+    /// 引数の観測値の格子を出力しない場合は`true`を返す。
+    fn skips(&self, value: Option<u16>) -> bool {
+        match (value, self.min_value) {
+            (Some(value), Some(min_value)) => value < min_value,
+            _ => false,
+        }
+    }
+
+    /// 観測値をCSVファイルに出力する文字列に変換する。
+    ///
+    /// 欠測値は`missing`で指定した文字列に変換する。
+    fn format_value(&self, value: Option<u16>) -> String {
+        match value {
+            Some(value) => value.to_string(),
+            None => self.missing.to_string(),
+        }
+    }
+
+    /// 出力する観測データの属性を、名前と値の組み合わせで返す。
+    ///
+    /// # 戻り値
+    ///
+    /// 名前と値の組み合わせ、出力する観測データの属性が指定されていない場合は空の`Vec`
+    fn metadata_entries(&self) -> Vec<(&'static str, String)> {
+        let Some(dp) = self.metadata else {
+            return vec![];
+        };
+
+        vec![
+            (
+                "observation_date_time",
+                dp.observation_date_time.format(DATETIME_FMT).unwrap(),
+            ),
+            (
+                "radar_operation_statuses",
+                format!("0x{:016X}", dp.radar_operation_statuses),
+            ),
+            ("number_of_amedas", dp.number_of_amedas.to_string()),
+        ]
+    }
+
+    /// 出力する観測データの属性を、CSVファイルのコメント行として出力する。
+    fn write_metadata_comment<W>(&self, writer: &mut W) -> std::io::Result<()>
+    where
+        W: Write,
+    {
+        let entries = self.metadata_entries();
+        if entries.is_empty() {
+            return Ok(());
+        }
+        let entries = entries
+            .iter()
+            .map(|(key, value)| format!("{key}={value}"))
+            .collect::<Vec<_>>();
+
+        writeln!(writer, "# {}", entries.join(","))
+    }
+
+    /// イテレーターが返した座標を、出力する座標に変換する。
+    ///
+    /// # 引数
+    ///
+    /// * `iterator_anchor` - イテレーターが返した座標の格子内の位置
+    /// * `lv` - イテレーターが返した座標と観測値
+    /// * `width` - 格子の幅（度）
+    /// * `height` - 格子の高さ（度）
+    ///
+    /// # 戻り値
+    ///
+    /// `(格子の中心の座標, 出力する座標)`、座標は`(経度, 緯度)`
+    fn anchored(
+        &self,
+        iterator_anchor: CoordinateAnchor,
+        lv: &LocationValue,
+        width: f64,
+        height: f64,
+    ) -> ((f64, f64), (f64, f64)) {
+        let center = iterator_anchor.shift_to_center(lv.longitude, lv.latitude, width, height);
+        let anchored = self
+            .anchor
+            .shift_from_center(center.0, center.1, width, height);
+
+        (center, anchored)
+    }
+}
+
 /// ジオメトリ付きCSVファイルを出力する。
 ///
 /// # 引数
@@ -1073,18 +3588,238 @@ pub fn output_csv_with_geom<W>(
 where
     W: Write,
 {
-    writeln!(writer, "longitude,latitude,value,geom")?;
-    for lv in iterator.flatten() {
-        let value_str = match lv.value {
-            Some(value) => value.to_string(),
-            None => String::new(),
-        };
-        let wkt = grid_wkt(lv.longitude, lv.latitude, grid_width, grid_height);
+    output_csv_with_geom_with_progress(writer, iterator, grid_width, grid_height, |_, _| {})
+}
+
+/// 進捗を通知しながら、ジオメトリ付きCSVファイルを出力する。
+///
+/// # 引数
+///
+/// * `iterator` - 観測値を順に取り出すイテレーター
+/// * `progress` - 観測値を1つ処理するたびに、処理した観測値の数と観測値の総数を受け取るクロージャー
+///   （観測値の総数が不明な場合は0）
+pub fn output_csv_with_geom_with_progress<W, F>(
+    writer: &mut W,
+    iterator: RapValueIterator,
+    grid_width: f64,
+    grid_height: f64,
+    progress: F,
+) -> std::io::Result<()>
+where
+    W: Write,
+    F: FnMut(usize, usize),
+{
+    output_csv_with_geom_with_options(
+        writer,
+        iterator,
+        grid_width,
+        grid_height,
+        &ExportOptions::default(),
+        progress,
+    )
+}
+
+/// オプションに従って、ジオメトリ付きCSVファイルを出力する。
+///
+/// 観測値の展開に失敗した場合は、出力を中断して`std::io::ErrorKind::Other`のエラーを返す。
+///
+/// # 引数
+///
+/// * `iterator` - 観測値を順に取り出すイテレーター
+/// * `options` - 観測値を出力するときのオプション
+/// * `progress` - 観測値を1つ処理するたびに、処理した観測値の数と観測値の総数を受け取るクロージャー
+///   （観測値の総数が不明な場合は0）
+pub fn output_csv_with_geom_with_options<W, F>(
+    writer: &mut W,
+    iterator: RapValueIterator,
+    grid_width: f64,
+    grid_height: f64,
+    options: &ExportOptions,
+    progress: F,
+) -> std::io::Result<()>
+where
+    W: Write,
+    F: FnMut(usize, usize),
+{
+    let meta = GridMetadata::from_iterator(&iterator, grid_width, grid_height, options.metadata);
+    let mut sink = CsvWithGeomSink::new(writer, options.clone());
+
+    drive_sink(iterator, &meta, &mut sink, progress).map_err(std::io::Error::other)
+}
+
+/// 格子の中心の座標と観測値を記録したCSVファイルを出力する。
+///
+/// `output_csv_with_geom`と異なり、格子を表現するジオメトリを出力しない。
+///
+/// # 引数
+///
+/// * `iterator` - 観測値を順に取り出すイテレーター
+pub fn output_csv_points<W>(writer: &mut W, iterator: RapValueIterator) -> std::io::Result<()>
+where
+    W: Write,
+{
+    output_csv_points_with_progress(writer, iterator, |_, _| {})
+}
+
+/// 進捗を通知しながら、格子の中心の座標と観測値を記録したCSVファイルを出力する。
+///
+/// # 引数
+///
+/// * `iterator` - 観測値を順に取り出すイテレーター
+/// * `progress` - 観測値を1つ処理するたびに、処理した観測値の数と観測値の総数を受け取るクロージャー
+///   （観測値の総数が不明な場合は0）
+pub fn output_csv_points_with_progress<W, F>(
+    writer: &mut W,
+    iterator: RapValueIterator,
+    progress: F,
+) -> std::io::Result<()>
+where
+    W: Write,
+    F: FnMut(usize, usize),
+{
+    output_csv_points_with_options(writer, iterator, &ExportOptions::default(), progress)
+}
+
+/// オプションに従って、格子の中心の座標と観測値を記録したCSVファイルを出力する。
+///
+/// 観測値の展開に失敗した場合は、出力を中断して`std::io::ErrorKind::Other`のエラーを返す。
+///
+/// # 引数
+///
+/// * `iterator` - 観測値を順に取り出すイテレーター
+/// * `options` - 観測値を出力するときのオプション
+/// * `progress` - 観測値を1つ処理するたびに、処理した観測値の数と観測値の総数を受け取るクロージャー
+///   （観測値の総数が不明な場合は0）
+pub fn output_csv_points_with_options<W, F>(
+    writer: &mut W,
+    iterator: RapValueIterator,
+    options: &ExportOptions,
+    mut progress: F,
+) -> std::io::Result<()>
+where
+    W: Write,
+    F: FnMut(usize, usize),
+{
+    let total = iterator.number_of_cells().unwrap_or(0);
+    let iterator_anchor = iterator.anchor;
+    let (width, height) = iterator.grid_size_deg();
+    options.write_metadata_comment(writer)?;
+    writeln!(writer, "longitude,latitude,value")?;
+    for (index, lv) in iterator.enumerate() {
+        progress(index + 1, total);
+        let lv = lv.map_err(std::io::Error::other)?;
+        if options.skips(lv.value) {
+            continue;
+        }
+        let value_str = options.format_value(lv.value);
+        let (_, (lon, lat)) = options.anchored(iterator_anchor, &lv, width, height);
         writeln!(
             writer,
-            "{},{},{},\"{}\"",
-            lv.longitude, lv.latitude, value_str, wkt
+            "{:.*},{:.*},{}",
+            options.precision, lon, options.precision, lat, value_str
+        )?;
+    }
+    writer.flush()?;
+
+    Ok(())
+}
+
+/// 複数の日時の観測値を、1行に1つの格子、1列に1つの日時を記録したCSVファイルに出力する。
+///
+/// 1行目は`longitude,latitude`に続けて、引数で指定された順に観測日時を記録する。
+/// 2行目以降は、格子の中心の経度と緯度に続けて、日時ごとの観測値を記録する（欠測値は空文字列）。
+/// 格子は`value_iterator`と同様に、最北西端の格子から経度方向、緯度方向の優先順位で出力する。
+///
+/// # 引数
+///
+/// * `reader` - RAPファイル・リーダー
+/// * `writer` - CSVの出力先
+/// * `datetimes` - 出力する観測値の日時
+pub fn output_csv_timeseries<W>(
+    reader: &RapReader,
+    writer: &mut W,
+    datetimes: &[PrimitiveDateTime],
+) -> RapReaderResult<()>
+where
+    W: Write,
+{
+    output_csv_timeseries_with_options(reader, writer, datetimes, &ExportOptions::default())
+}
+
+/// オプションに従って、複数の日時の観測値を、1行に1つの格子、1列に1つの日時を記録したCSVファイルに出力する。
+///
+/// オプションのうち、緯度と経度の小数点以下の桁数と欠測値の文字列を適用する。
+/// 1つの格子に複数の観測値を記録するため、出力する観測値の最小値、格子内の位置及び観測データの属性は適用しない。
+///
+/// # 引数
+///
+/// * `reader` - RAPファイル・リーダー
+/// * `writer` - CSVの出力先
+/// * `datetimes` - 出力する観測値の日時
+/// * `options` - 観測値を出力するときのオプション
+pub fn output_csv_timeseries_with_options<W>(
+    reader: &RapReader,
+    writer: &mut W,
+    datetimes: &[PrimitiveDateTime],
+    options: &ExportOptions,
+) -> RapReaderResult<()>
+where
+    W: Write,
+{
+    reader.ensure_value_iterator_supported()?;
+    let grids = datetimes
+        .iter()
+        .map(|dt| reader.to_grid(*dt))
+        .collect::<RapReaderResult<Vec<_>>>()?;
+
+    write!(writer, "longitude,latitude")?;
+    for dt in datetimes {
+        write!(writer, ",{}", dt.format(DATETIME_FMT).unwrap())?;
+    }
+    writeln!(writer)?;
+    for (index, (_, _, lon, lat)) in reader.cell_coordinates().enumerate() {
+        write!(
+            writer,
+            "{:.*},{:.*}",
+            options.precision, lon, options.precision, lat
         )?;
+        for grid in grids.iter() {
+            write!(writer, ",{}", options.format_value(grid[index]))?;
+        }
+        writeln!(writer)?;
+    }
+    writer.flush()?;
+
+    Ok(())
+}
+
+/// 観測値を画素としたラスターを地理参照するワールドファイルを出力する。
+///
+/// ワールドファイルは、経度方向の画素の大きさ、2つの回転成分、緯度方向の画素の大きさ（負の値）、
+/// 最北西端の画素の中心の経度と緯度を、度単位で1行ずつ記録する。
+/// 画素は、最北西端の格子から経度方向、緯度方向の優先順位で観測値を並べたものとする。
+///
+/// # 引数
+///
+/// * `reader` - RAPファイル・リーダー
+/// * `path` - 出力するワールドファイルのパス
+pub fn write_world_file(reader: &RapReader, path: &Path) -> RapReaderResult<()> {
+    let file = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(path)?;
+    let mut writer = std::io::BufWriter::new(file);
+    let lines = [
+        reader.grid_width_deg(),
+        0.0,
+        0.0,
+        -reader.grid_height_deg(),
+        reader.grid_start_longitude_deg(),
+        reader.grid_start_latitude_deg(),
+    ];
+    for line in lines {
+        writeln!(writer, "{line}")?;
     }
     writer.flush()?;
 
@@ -1096,24 +3831,44 @@ where
 /// # 引数
 ///
 /// * `longitude` - 格子の中心の経度（度）
-/// * `latitude` - 格子の中心の経度（度）
+/// * `latitude` - 格子の中心の緯度（度）
 /// * `width` - 格子の幅（度）
 /// * `height` - 格子の高さ（度）
+/// * `precision` - 座標の小数点以下の桁数
 ///
 /// # 戻り値
 ///
 /// 格子を表現するOGC Well-known TEXT
-fn grid_wkt(longitude: f64, latitude: f64, width: f64, height: f64) -> String {
-    let half_width = width / 2.0;
-    let half_height = height / 2.0;
-    let left = longitude - half_width;
-    let right = longitude + half_width;
-    let top = latitude + half_height;
-    let bottom = latitude - half_height;
+fn grid_wkt(longitude: f64, latitude: f64, width: f64, height: f64, precision: usize) -> String {
+    let (left, bottom, right, top) = grid_corners(longitude, latitude, width, height);
 
     // 左上、右上、右下、左下、左上の順にポリゴンの座標を並べる
     format!(
-        "POLYGON(({0} {3},{2} {3},{2} {1},{0} {1}, {0} {3}))",
-        left, bottom, right, top
+        "POLYGON(({0:.4$} {3:.4$},{2:.4$} {3:.4$},{2:.4$} {1:.4$},{0:.4$} {1:.4$}, {0:.4$} {3:.4$}))",
+        left, bottom, right, top, precision
+    )
+}
+
+/// 格子の西端の経度、南端の緯度、東端の経度、北端の緯度を返す。
+///
+/// # 引数
+///
+/// * `longitude` - 格子の中心の経度（度）
+/// * `latitude` - 格子の中心の緯度（度）
+/// * `width` - 格子の幅（度）
+/// * `height` - 格子の高さ（度）
+///
+/// # 戻り値
+///
+/// `(西端の経度, 南端の緯度, 東端の経度, 北端の緯度)`
+fn grid_corners(longitude: f64, latitude: f64, width: f64, height: f64) -> (f64, f64, f64, f64) {
+    let half_width = width / 2.0;
+    let half_height = height / 2.0;
+
+    (
+        longitude - half_width,
+        latitude - half_height,
+        longitude + half_width,
+        latitude + half_height,
     )
 }
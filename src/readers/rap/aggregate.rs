@@ -0,0 +1,236 @@
+use std::collections::VecDeque;
+
+use time::PrimitiveDateTime;
+
+use super::{GridDefinition, RapReader, RapReaderError, RapReaderResult, MISSING_VALUE};
+
+/// 格子を集約する方法
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Aggregation {
+    /// 平均値（小数点以下は四捨五入）
+    Mean,
+    /// 最大値
+    Max,
+    /// 合計値（欠測値と区別するため、`u16::MAX - 1`を超える場合は`u16::MAX - 1`）
+    Sum,
+}
+
+/// 格子数が集約する格子数で割り切れない場合の扱い
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RemainderPolicy {
+    /// エラーを返す。
+    #[default]
+    Error,
+    /// 東端と南端の余った格子を切り捨てる。
+    Trim,
+    /// 東端と南端の余った格子を、欠測値の格子で補って集約する。
+    Pad,
+}
+
+/// 集約した観測値の最大値
+///
+/// `u16::MAX`は欠測値を示すため、集約した観測値は`u16::MAX - 1`を上限とする。
+const MAX_AGGREGATED_VALUE: u16 = MISSING_VALUE - 1;
+
+/// 集約中の観測値
+#[derive(Debug, Clone, Copy, Default)]
+struct Accumulator {
+    /// 欠測値を除いた観測値の数
+    count: u64,
+    /// 欠測値を除いた観測値の合計
+    sum: u64,
+    /// 欠測値を除いた観測値の最大値
+    max: u16,
+}
+
+impl Accumulator {
+    fn push(&mut self, value: u16) {
+        self.count += 1;
+        self.sum += value as u64;
+        self.max = self.max.max(value);
+    }
+
+    fn finish(&self, aggregation: Aggregation) -> Option<u16> {
+        if self.count == 0 {
+            return None;
+        }
+        let value = match aggregation {
+            Aggregation::Mean => (self.sum + self.count / 2) / self.count,
+            Aggregation::Max => self.max as u64,
+            Aggregation::Sum => self.sum,
+        };
+
+        // 欠測値を示す`u16::MAX`と区別するため、`u16::MAX - 1`を上限とする
+        Some(value.min(MAX_AGGREGATED_VALUE as u64) as u16)
+    }
+}
+
+impl RapReader {
+    /// 引数で指定された日時の観測値を、より粗い格子に集約する。
+    ///
+    /// 経度方向と緯度方向に`factor`個ずつ並んだ格子を1つの格子に集約する。
+    /// 集約するときは欠測値を除外して、集約するすべての格子が欠測値の場合のみ欠測値とする。
+    /// `factor`が0の場合、経度方向または緯度方向の格子数より大きい場合、
+    /// 及び集約後の格子系定義が10e-6度単位で表現できない場合は、
+    /// `RapReaderError::InvalidDownsampleFactor`を返す。
+    ///
+    /// # 引数
+    ///
+    /// * `dt` - 集約する観測値の日時
+    /// * `factor` - 経度方向と緯度方向に集約する格子数
+    /// * `aggregation` - 集約する方法
+    /// * `policy` - 格子数が`factor`で割り切れない場合の扱い
+    ///
+    /// # 戻り値
+    ///
+    /// 集約後の格子系定義と、集約後の観測値を記録順に格納した`Vec`
+    pub fn downsample(
+        &self,
+        dt: PrimitiveDateTime,
+        factor: u16,
+        aggregation: Aggregation,
+        policy: RemainderPolicy,
+    ) -> RapReaderResult<(GridDefinition, Vec<Option<u16>>)> {
+        let src = self.grid_definition;
+        if factor == 0 || src.number_of_h_grids < factor || src.number_of_v_grids < factor {
+            return Err(RapReaderError::InvalidDownsampleFactor(factor));
+        }
+        let src_h = src.number_of_h_grids as usize;
+        let src_v = src.number_of_v_grids as usize;
+        let f = factor as usize;
+        let (dst_h, dst_v) = match policy {
+            RemainderPolicy::Error => {
+                if !src_h.is_multiple_of(f) || !src_v.is_multiple_of(f) {
+                    return Err(RapReaderError::GridNotDivisible {
+                        number_of_h_grids: src.number_of_h_grids,
+                        number_of_v_grids: src.number_of_v_grids,
+                        factor,
+                    });
+                }
+                (src_h / f, src_v / f)
+            }
+            RemainderPolicy::Trim => (src_h / f, src_v / f),
+            RemainderPolicy::Pad => (src_h.div_ceil(f), src_v.div_ceil(f)),
+        };
+
+        // 集約後の最北西端の格子の中心は、集約前の最北西端の格子の中心から、
+        // 集約前の格子(factor - 1)/2個分だけ南東に位置する
+        let invalid_factor = || RapReaderError::InvalidDownsampleFactor(factor);
+        let offset = factor as u32 - 1;
+        let grid_width = src
+            .grid_width
+            .checked_mul(factor as u32)
+            .ok_or_else(invalid_factor)?;
+        let grid_height = src
+            .grid_height
+            .checked_mul(factor as u32)
+            .ok_or_else(invalid_factor)?;
+        let start_grid_latitude = src
+            .grid_height
+            .checked_mul(offset)
+            .and_then(|shift| src.start_grid_latitude.checked_sub(shift / 2))
+            .ok_or_else(invalid_factor)?;
+        let start_grid_longitude = src
+            .grid_width
+            .checked_mul(offset)
+            .and_then(|shift| src.start_grid_longitude.checked_add(shift / 2))
+            .ok_or_else(invalid_factor)?;
+        let dst = GridDefinition {
+            map_type: src.map_type,
+            start_grid_latitude,
+            start_grid_longitude,
+            grid_width,
+            grid_height,
+            number_of_h_grids: dst_h as u16,
+            number_of_v_grids: dst_v as u16,
+        };
+
+        let mut accumulators = vec![Accumulator::default(); dst_h * dst_v];
+        for (index, value) in self.to_grid(dt)?.into_iter().enumerate() {
+            let (row, col) = (index / src_h / f, index % src_h / f);
+            if dst_v <= row || dst_h <= col {
+                continue;
+            }
+            if let Some(value) = value {
+                accumulators[row * dst_h + col].push(value);
+            }
+        }
+        let values = accumulators
+            .iter()
+            .map(|acc| acc.finish(aggregation))
+            .collect();
+
+        Ok((dst, values))
+    }
+
+    /// 連続する観測データの観測値を格子ごとに積算した値を、観測日時の順に返すイテレーターを返す。
+    ///
+    /// 観測日時ごとに、その観測日時と、それより前の`window - 1`個の観測データの観測値を積算する。
+    /// 観測データは記録されている観測日時の順に数えるため、記録されていない観測日時は積算しない。
+    /// 積算するときは欠測値を除外して、積算するすべての観測値が欠測値の場合のみ欠測値とする。
+    /// 積算した値が`u16::MAX - 1`を超える場合は、欠測値を示す`u16::MAX`と区別するため`u16::MAX - 1`とする。
+    ///
+    /// # 引数
+    ///
+    /// * `window` - 積算する観測データの数
+    /// * `partial` - 最初の`window - 1`個の観測日時で、積算する観測データが`window`個に満たない場合に、
+    ///   それまでの観測データを積算した値を返す場合は`true`、返さない場合は`false`
+    ///
+    /// # 戻り値
+    ///
+    /// 観測日時と、積算した値を記録順に格納した`Vec`を返すイテレーター
+    pub fn rolling_accumulation(
+        &self,
+        window: usize,
+        partial: bool,
+    ) -> impl Iterator<Item = RapReaderResult<(PrimitiveDateTime, Vec<Option<u16>>)>> + '_ {
+        let mut dts = self
+            .data_properties()
+            .iter()
+            .map(|dp| dp.observation_date_time)
+            .collect::<Vec<_>>();
+        dts.sort();
+        let mut dts = dts.into_iter();
+        let mut invalid_window = (window == 0).then_some(window);
+        // 観測データの数を超える格子は保持しないため、観測データの数を上限に領域を確保
+        let capacity = window.min(self.data_properties().len());
+        let mut grids: VecDeque<Vec<Option<u16>>> = VecDeque::with_capacity(capacity);
+
+        std::iter::from_fn(move || {
+            if window == 0 {
+                return invalid_window
+                    .take()
+                    .map(|w| Err(RapReaderError::InvalidAccumulationWindow(w)));
+            }
+            loop {
+                let dt = dts.next()?;
+                let grid = match self.to_grid(dt) {
+                    Ok(grid) => grid,
+                    Err(e) => return Some(Err(e)),
+                };
+                if grids.len() == window {
+                    grids.pop_front();
+                }
+                grids.push_back(grid);
+                if grids.len() < window && !partial {
+                    continue;
+                }
+
+                let mut accumulators = vec![Accumulator::default(); self.grid_cell_count()];
+                for grid in grids.iter() {
+                    for (acc, value) in accumulators.iter_mut().zip(grid) {
+                        if let Some(value) = value {
+                            acc.push(*value);
+                        }
+                    }
+                }
+                let values = accumulators
+                    .iter()
+                    .map(|acc| acc.finish(Aggregation::Sum))
+                    .collect();
+
+                return Some(Ok((dt, values)));
+            }
+        })
+    }
+}
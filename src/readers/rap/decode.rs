@@ -0,0 +1,335 @@
+use super::{
+    is_missing, GridDefinition, LevelRepetition, RapReaderError, RapReaderResult, REPETITION_OFFSET,
+};
+
+/// ランレングス圧縮の展開エラー
+///
+/// オフセットは、圧縮データの先頭からのバイト位置である。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum DecodeError {
+    /// 圧縮データが途中で終了している
+    #[error("圧縮データが途中で終了しています。(オフセット: {offset})")]
+    UnexpectedEnd { offset: usize },
+
+    /// 判別できないランレングス圧縮バイト
+    ///
+    /// `cell_index`は、判別できないバイトを展開した観測値を格納するはずだった格子の、
+    /// 最北西端の格子からの記録順のインデックスである。
+    #[error("圧縮データに判別できないバイトが見つかりました。`0x{byte:x}` (オフセット: {offset}、格子のインデックス: {cell_index})")]
+    UnknownRunLengthByte {
+        byte: u8,
+        offset: usize,
+        cell_index: usize,
+    },
+
+    /// レベル反復表に存在しないインデックス
+    #[error("レベル反復表に存在しないインデックスです。`{index}` (オフセット: {offset})")]
+    LevelRepetitionOutOfRange { index: u8, offset: usize },
+
+    /// 観測値表に存在しないレベル
+    #[error("観測値表に存在しないレベルです。`{level}` (オフセット: {offset})")]
+    LevelOutOfRange { level: u8, offset: usize },
+
+    /// 展開した観測値の数が格子数と一致しない
+    #[error("展開した観測値の数が格子数と一致しません。格子数: {expected}、観測値の数: {actual}")]
+    CellCountMismatch { expected: usize, actual: usize },
+}
+
+/// 圧縮データを展開して、格子ごとの観測値を返すトレイト
+///
+/// RAPファイルの圧縮方法ごとに実装する。
+/// 組み込みの実装は、ランレングス圧縮（圧縮方法1）を展開する`RunLengthDecompressor`である。
+pub trait Decompressor {
+    /// 圧縮データを展開する。
+    ///
+    /// # 引数
+    ///
+    /// * `compressed` - 圧縮データ
+    /// * `grid_definition` - 格子系定義
+    /// * `value_by_levels` - レベルごとの観測値
+    /// * `level_repetitions` - レベルと反復数の組み合わせ
+    ///
+    /// # 戻り値
+    ///
+    /// 最北西端の格子から経度方向、緯度方向の優先順位で観測値を格納した`Vec`（欠測値は`None`）
+    fn decompress(
+        &self,
+        compressed: &[u8],
+        grid_definition: &GridDefinition,
+        value_by_levels: &[u16],
+        level_repetitions: &[LevelRepetition],
+    ) -> RapReaderResult<Vec<Option<u16>>>;
+}
+
+/// ランレングス圧縮（圧縮方法1）を展開する`Decompressor`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RunLengthDecompressor;
+
+impl Decompressor for RunLengthDecompressor {
+    fn decompress(
+        &self,
+        compressed: &[u8],
+        grid_definition: &GridDefinition,
+        value_by_levels: &[u16],
+        level_repetitions: &[LevelRepetition],
+    ) -> RapReaderResult<Vec<Option<u16>>> {
+        decode_grid(
+            compressed,
+            value_by_levels,
+            level_repetitions,
+            grid_definition.number_of_h_grids,
+            grid_definition.number_of_v_grids,
+        )
+        .map_err(RapReaderError::from)
+    }
+}
+
+/// ランレングス圧縮の種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(super) enum RunLengthKind {
+    /// レベル反復表によるランレングス圧縮(a)
+    LevelRepetition,
+    /// レベル反復表によらないランレングス圧縮(b)
+    Repetition,
+    /// 頻度が多い単独のレベル値(c)
+    FrequentLevel,
+    /// 頻度が少ない単独のレベル値(d)
+    InfrequentLevel,
+}
+
+/// 展開した観測値
+pub(super) struct ExpandedValue {
+    /// ランレングス圧縮の種類
+    pub(super) kind: RunLengthKind,
+    /// レベル
+    pub(super) level: u8,
+    /// 観測値
+    pub(super) value: u16,
+    /// 観測値を返却する回数
+    pub(super) number_of_repetitions: u16,
+}
+
+/// ランレングス圧縮された観測値を1つ展開する。
+///
+/// # 引数
+///
+/// * `offset` - 展開する観測値の最初のバイトの、圧縮データの先頭からのバイト位置
+/// * `cell_index` - 展開する観測値を格納する最初の格子の、最北西端の格子からの記録順のインデックス
+/// * `next_byte` - 圧縮データを1バイトずつ返すクロージャー
+/// * `value_by_levels` - レベルごとの観測値
+/// * `level_repetitions` - レベルと反復数の組み合わせ
+///
+/// # 戻り値
+///
+/// 展開した観測値
+pub(super) fn expand_run_length<F>(
+    offset: usize,
+    cell_index: usize,
+    mut next_byte: F,
+    value_by_levels: &[u16],
+    level_repetitions: &[LevelRepetition],
+) -> Result<ExpandedValue, DecodeError>
+where
+    F: FnMut() -> Result<u8, DecodeError>,
+{
+    let value_of = |level: u8| {
+        value_by_levels
+            .get(level as usize)
+            .copied()
+            .ok_or(DecodeError::LevelOutOfRange { level, offset })
+    };
+
+    // 1バイト読み込み
+    let buf = next_byte()?;
+    let expanded_value = if buf & 0x80 == 0x00 {
+        // レベル反復表によるランレングス圧縮(a)
+        let lr = level_repetitions
+            .get(buf as usize)
+            .ok_or(DecodeError::LevelRepetitionOutOfRange { index: buf, offset })?;
+        ExpandedValue {
+            kind: RunLengthKind::LevelRepetition,
+            level: lr.level,
+            value: value_of(lr.level)?,
+            number_of_repetitions: lr.actual_repetition(),
+        }
+    } else if buf & 0xE0 == 0xC0 {
+        // レベル反復表によらないランレングス圧縮(b)
+        let level = buf & 0x1F;
+        ExpandedValue {
+            kind: RunLengthKind::Repetition,
+            level,
+            value: value_of(level)?,
+            number_of_repetitions: next_byte()? as u16 + REPETITION_OFFSET,
+        }
+    } else if buf & 0xC0 == 0x80 {
+        // 頻度が多い単独のレベル値(c)
+        let level = buf & 0x3F;
+        ExpandedValue {
+            kind: RunLengthKind::FrequentLevel,
+            level,
+            value: value_of(level)?,
+            number_of_repetitions: 1,
+        }
+    } else if buf == 0xFE {
+        // 頻度が少ない単独のレベル値(d)
+        let level = next_byte()?;
+        ExpandedValue {
+            kind: RunLengthKind::InfrequentLevel,
+            level,
+            value: value_of(level)?,
+            number_of_repetitions: 1,
+        }
+    } else {
+        return Err(DecodeError::UnknownRunLengthByte {
+            byte: buf,
+            offset,
+            cell_index,
+        });
+    };
+
+    Ok(expanded_value)
+}
+
+/// メモリ上の圧縮データを展開して、格子ごとの観測値を返す。
+///
+/// ファイルを読み込まずに、圧縮データのバイト列のみから観測値を展開する。
+///
+/// # 引数
+///
+/// * `compressed` - 圧縮データ
+/// * `value_by_levels` - レベルごとの観測値
+/// * `level_repetitions` - レベルと反復数の組み合わせ
+/// * `number_of_h_grids` - 経度方向の格子数
+/// * `number_of_v_grids` - 緯度方向の格子数
+///
+/// # 戻り値
+///
+/// 最北西端の格子から経度方向、緯度方向の優先順位で観測値を格納した`Vec`（欠測値は`None`）
+pub fn decode_grid(
+    compressed: &[u8],
+    value_by_levels: &[u16],
+    level_repetitions: &[LevelRepetition],
+    number_of_h_grids: u16,
+    number_of_v_grids: u16,
+) -> Result<Vec<Option<u16>>, DecodeError> {
+    let mut values = Vec::new();
+    decode_grid_into(
+        compressed,
+        value_by_levels,
+        level_repetitions,
+        number_of_h_grids,
+        number_of_v_grids,
+        &mut values,
+    )?;
+
+    Ok(values)
+}
+
+/// メモリ上の圧縮データを展開して、記録順に観測値を返す。
+///
+/// 格子系定義を参照せずに、圧縮データのバイト列のみから観測値を展開する。
+/// `decode_grid`と異なり、展開した観測値の数を格子数と照合しないため、
+/// `RapReader::compressed_block`で取得した圧縮データの展開結果を調べるときに使用する。
+///
+/// # 引数
+///
+/// * `compressed` - 圧縮データ
+/// * `value_by_levels` - レベルごとの観測値
+/// * `level_repetitions` - レベルと反復数の組み合わせ
+///
+/// # 戻り値
+///
+/// 圧縮データに記録されている順に観測値を格納した`Vec`（欠測値は`None`）
+pub fn decode_run_length(
+    compressed: &[u8],
+    value_by_levels: &[u16],
+    level_repetitions: &[LevelRepetition],
+) -> RapReaderResult<Vec<Option<u16>>> {
+    let mut values = Vec::new();
+    expand_all_into(
+        compressed,
+        value_by_levels,
+        level_repetitions,
+        usize::MAX,
+        &mut values,
+    )
+    .map_err(RapReaderError::from)?;
+
+    Ok(values)
+}
+
+/// メモリ上の圧縮データを展開して、格子ごとの観測値を引数で指定された`Vec`に格納する。
+///
+/// `values`は空にしてから観測値を格納するため、確保済みの領域を再利用できる。
+/// 引数は`decode_grid`と同じである。
+pub(super) fn decode_grid_into(
+    compressed: &[u8],
+    value_by_levels: &[u16],
+    level_repetitions: &[LevelRepetition],
+    number_of_h_grids: u16,
+    number_of_v_grids: u16,
+    values: &mut Vec<Option<u16>>,
+) -> Result<(), DecodeError> {
+    let expected = number_of_h_grids as usize * number_of_v_grids as usize;
+    values.clear();
+    values.reserve(expected);
+    let actual = expand_all_into(
+        compressed,
+        value_by_levels,
+        level_repetitions,
+        expected,
+        values,
+    )?;
+    if actual != expected {
+        return Err(DecodeError::CellCountMismatch { expected, actual });
+    }
+
+    Ok(())
+}
+
+/// 圧縮データをすべて展開して、観測値を引数で指定された`Vec`の末尾に追加する。
+///
+/// `values`には最大で`limit`個の観測値を格納する。
+///
+/// # 戻り値
+///
+/// 圧縮データに記録されていた観測値の数（`limit`を超えた観測値も数える）
+fn expand_all_into(
+    compressed: &[u8],
+    value_by_levels: &[u16],
+    level_repetitions: &[LevelRepetition],
+    limit: usize,
+    values: &mut Vec<Option<u16>>,
+) -> Result<usize, DecodeError> {
+    let mut actual = 0;
+    let mut position = 0;
+    while position < compressed.len() {
+        let offset = position;
+        let next_byte = || {
+            let byte = compressed
+                .get(position)
+                .copied()
+                .ok_or(DecodeError::UnexpectedEnd { offset: position })?;
+            position += 1;
+            Ok(byte)
+        };
+        let ev = expand_run_length(
+            offset,
+            actual,
+            next_byte,
+            value_by_levels,
+            level_repetitions,
+        )?;
+        let value = if is_missing(ev.value) {
+            None
+        } else {
+            Some(ev.value)
+        };
+        let count = ev.number_of_repetitions as usize;
+        let remaining = limit.saturating_sub(values.len());
+        values.extend(std::iter::repeat_n(value, count.min(remaining)));
+        actual += count;
+    }
+
+    Ok(actual)
+}
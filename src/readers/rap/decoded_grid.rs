@@ -0,0 +1,200 @@
+use time::PrimitiveDateTime;
+
+use super::{
+    decode_grid_into, locate_cell, read_compressed_data, CompressionMethod, Origin, RapReader,
+    RapReaderError, RapReaderResult,
+};
+
+impl RapReader {
+    /// 引数で指定された日時の観測値を展開して、格子ごとに参照できる`DecodedGrid`を返す。
+    ///
+    /// 観測値を1度だけ展開するため、多数の格子の観測値を参照する場合に、
+    /// 格子ごとに圧縮データを走査するよりも高速である。
+    ///
+    /// # 引数
+    ///
+    /// * `dt` - 観測値を展開する日時
+    ///
+    /// # 戻り値
+    ///
+    /// 展開した観測値
+    pub fn decode(&self, dt: PrimitiveDateTime) -> RapReaderResult<DecodedGrid> {
+        Ok(self.decoded_grid(dt, self.to_grid(dt)?))
+    }
+
+    /// ファイルに記録されているすべての日時の観測値を展開して、日時と組み合わせて返す。
+    ///
+    /// 観測値は、データ部へのインデックスに記録されている順に展開する。
+    /// 1つのリーダーで圧縮データを順に読み込み、日時ごとに格子数分の領域を確保して展開する。
+    /// すべての日時の観測値をメモリに展開するため、1時間間隔のファイルでは
+    /// 格子数の24倍の観測値（1格子あたり4バイト）の領域が必要である。
+    ///
+    /// # 戻り値
+    ///
+    /// 観測日時と展開した観測値を格納した`Vec`、
+    /// いずれかの日時の展開に失敗した場合は、その日時を示す`RapReaderError::DecodeFailed`
+    pub fn decode_all(&self) -> RapReaderResult<Vec<(PrimitiveDateTime, DecodedGrid)>> {
+        if let CompressionMethod::Other(method) = self.compression_method_kind() {
+            return Err(RapReaderError::CompressionMethodUnsupported(method));
+        }
+        let mut reader = self.source.open()?;
+        let mut grids = Vec::with_capacity(self.data_properties().len());
+        for (index, dp) in self.data_properties().iter().enumerate() {
+            let dt = dp.observation_date_time;
+            let mut values = Vec::with_capacity(self.grid_cell_count());
+            self.resolve_data_property_at(index)
+                .and_then(|dp| {
+                    let compressed = read_compressed_data(&mut reader, dp)?;
+                    if compressed.is_empty() {
+                        return Err(RapReaderError::EmptyBlock(dt));
+                    }
+                    decode_grid_into(
+                        &compressed,
+                        self.value_by_levels(),
+                        self.level_repetitions(),
+                        self.number_of_h_grids(),
+                        self.number_of_v_grids(),
+                        &mut values,
+                    )
+                    .map_err(RapReaderError::from)
+                })
+                .map_err(|e| RapReaderError::DecodeFailed {
+                    datetime: dt,
+                    source: Box::new(e),
+                })?;
+            grids.push((dt, self.decoded_grid(dt, values)));
+        }
+
+        Ok(grids)
+    }
+
+    /// 展開した観測値から`DecodedGrid`を構築する。
+    fn decoded_grid(&self, dt: PrimitiveDateTime, values: Vec<Option<u16>>) -> DecodedGrid {
+        DecodedGrid {
+            datetime: dt,
+            values,
+            width: self.number_of_h_grids(),
+            height: self.number_of_v_grids(),
+            bounds: self.grid_bounds(),
+            cell_size: (self.grid_width_deg(), self.grid_height_deg()),
+        }
+    }
+}
+
+/// ある日時の展開した観測値
+///
+/// 観測値は、最北西端の格子から経度方向、緯度方向の優先順位で格納している。
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecodedGrid {
+    /// 観測日時
+    datetime: PrimitiveDateTime,
+    /// 記録順に格納した観測値（欠測値は`None`）
+    values: Vec<Option<u16>>,
+    /// 経度方向の格子数
+    width: u16,
+    /// 緯度方向の格子数
+    height: u16,
+    /// 観測範囲（`(最小経度, 最小緯度, 最大経度, 最大緯度)`）
+    bounds: (f64, f64, f64, f64),
+    /// 格子の幅と高さ（度）
+    cell_size: (f64, f64),
+}
+
+impl DecodedGrid {
+    /// 観測日時を返す。
+    pub fn datetime(&self) -> PrimitiveDateTime {
+        self.datetime
+    }
+
+    /// 経度方向の格子数を返す。
+    pub fn width(&self) -> u16 {
+        self.width
+    }
+
+    /// 緯度方向の格子数を返す。
+    pub fn height(&self) -> u16 {
+        self.height
+    }
+
+    /// 観測範囲全体の経度と緯度の範囲を度単位で返す。
+    ///
+    /// # 戻り値
+    ///
+    /// `(最小経度, 最小緯度, 最大経度, 最大緯度)`
+    pub fn bounds(&self) -> (f64, f64, f64, f64) {
+        self.bounds
+    }
+
+    /// 記録順に格納した観測値を返す。
+    pub fn values(&self) -> &[Option<u16>] {
+        &self.values
+    }
+
+    /// 引数で指定された格子の並び順で、観測値を格納した`Vec`を返す。
+    ///
+    /// # 引数
+    ///
+    /// * `origin` - 格子の並び順の原点
+    ///
+    /// # 戻り値
+    ///
+    /// 観測値を格納した`Vec`（欠測値は`None`）
+    pub fn values_with_origin(&self, origin: Origin) -> Vec<Option<u16>> {
+        let mut values = self.values.clone();
+        origin.reorder(&mut values, self.width as usize);
+
+        values
+    }
+
+    /// 引数で指定された行と列の格子の観測値を返す。
+    ///
+    /// # 引数
+    ///
+    /// * `row` - 北からの行のインデックス
+    /// * `col` - 西からの列のインデックス
+    ///
+    /// # 戻り値
+    ///
+    /// 観測値、欠測値または観測範囲外の場合は`None`
+    pub fn get(&self, row: u16, col: u16) -> Option<u16> {
+        if self.height <= row || self.width <= col {
+            return None;
+        }
+
+        self.get_linear(row as usize * self.width as usize + col as usize)
+    }
+
+    /// 引数で指定された記録順のインデックスの格子の観測値を返す。
+    ///
+    /// # 引数
+    ///
+    /// * `index` - 最北西端の格子から経度方向、緯度方向の優先順位で数えた格子のインデックス
+    ///
+    /// # 戻り値
+    ///
+    /// 観測値、欠測値または観測範囲外の場合は`None`
+    pub fn get_linear(&self, index: usize) -> Option<u16> {
+        self.values.get(index).copied().flatten()
+    }
+
+    /// 引数で指定された座標を含む格子の観測値を返す。
+    ///
+    /// # 引数
+    ///
+    /// * `latitude` - 緯度（度）
+    /// * `longitude` - 経度（度）
+    ///
+    /// # 戻り値
+    ///
+    /// 観測値、欠測値または観測範囲外の座標の場合は`None`
+    pub fn value_at(&self, latitude: f64, longitude: f64) -> Option<u16> {
+        locate_cell(
+            self.bounds,
+            self.cell_size,
+            (self.width, self.height),
+            longitude,
+            latitude,
+        )
+        .and_then(|index| self.get_linear(index))
+    }
+}
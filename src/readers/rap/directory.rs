@@ -0,0 +1,58 @@
+use std::path::Path;
+
+use time::PrimitiveDateTime;
+
+use super::{RapReader, RapReaderError, RapReaderResult};
+
+impl RapReader {
+    /// ディレクトリに格納されているすべてのRAPファイルを開く。
+    ///
+    /// 拡張子が`RAP`（大文字と小文字を区別しない）のファイルを開き、記録されている最も古い観測日時の順に並べる。
+    /// すべてのRAPファイルの格子系定義が一致しない場合はエラーを返す。
+    ///
+    /// # 引数
+    ///
+    /// * `dir` - RAPファイルを格納したディレクトリのパス
+    ///
+    /// # 戻り値
+    ///
+    /// 最も古い観測日時の順に並べた`RapReader`
+    pub fn open_dir(dir: &Path) -> RapReaderResult<Vec<RapReader>> {
+        let entries = std::fs::read_dir(dir).map_err(|e| RapReaderError::Open(format!("{e}")))?;
+        let mut readers = vec![];
+        for entry in entries {
+            let path = entry
+                .map_err(|e| RapReaderError::Open(format!("{e}")))?
+                .path();
+            let is_rap = path
+                .extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("rap"));
+            if path.is_file() && is_rap {
+                readers.push(RapReader::new(&path)?);
+            }
+        }
+        readers.sort_by_key(|reader| reader.observation_range().map(|(min, _)| min));
+
+        if let Some(first) = readers.first() {
+            for reader in readers.iter().skip(1) {
+                first.ensure_grid_definition_matches(reader)?;
+            }
+        }
+
+        Ok(readers)
+    }
+}
+
+/// 引数で指定された日時の観測データを記録している`RapReader`を返す。
+///
+/// # 引数
+///
+/// * `readers` - 観測データを探す`RapReader`
+/// * `dt` - 観測データの日時
+///
+/// # 戻り値
+///
+/// 観測データを記録している`RapReader`、観測データを記録している`RapReader`がない場合は`None`
+pub fn find_grid(readers: &[RapReader], dt: PrimitiveDateTime) -> Option<&RapReader> {
+    readers.iter().find(|reader| reader.contains(dt))
+}
@@ -0,0 +1,80 @@
+use time::{Date, Month};
+
+use super::{RapReader, RapReaderError, RapReaderResult, RapSource};
+
+/// RAPファイルのファイル名から、観測データを記録した日付を取得する。
+///
+/// RAPファイルのファイル名は`J2001401.RAP`のように、英字1文字、西暦4桁、月1桁、日2桁で構成されている。
+/// 月は1月から9月を`1`から`9`で、10月から12月を`A`から`C`で表現する。
+/// 拡張子は無視するため、`J2001401.RAP.gz`のようなファイル名からも日付を取得できる。
+///
+/// # 引数
+///
+/// * `name` - RAPファイルのファイル名
+///
+/// # 戻り値
+///
+/// 観測データを記録した日付、ファイル名が規則に従っていない場合は`None`
+pub fn parse_rap_filename(name: &str) -> Option<Date> {
+    let stem = name.split('.').next()?;
+    let bytes = stem.as_bytes();
+    if bytes.len() != 8 || !bytes[0].is_ascii_alphabetic() {
+        return None;
+    }
+    let year = parse_digits(&bytes[1..5])? as i32;
+    let month = match bytes[5].to_ascii_uppercase() {
+        b @ b'1'..=b'9' => b - b'0',
+        b @ b'A'..=b'C' => b - b'A' + 10,
+        _ => return None,
+    };
+    let day = parse_digits(&bytes[6..8])? as u8;
+
+    Date::from_calendar_date(year, Month::try_from(month).ok()?, day).ok()
+}
+
+/// ASCIIの数字の列を整数に変換する。
+fn parse_digits(bytes: &[u8]) -> Option<u32> {
+    bytes.iter().try_fold(0u32, |acc, b| {
+        b.is_ascii_digit().then(|| acc * 10 + (b - b'0') as u32)
+    })
+}
+
+impl RapReader {
+    /// ファイル名から取得した観測データを記録した日付を、記録されている観測日時と照合して返す。
+    ///
+    /// 最も古い観測日時の日付と、ファイル名から取得した日付が一致することを確認する。
+    /// gzipで圧縮されたファイルを展開して読み込んだ場合は、展開元のファイル名と照合する。
+    /// アーカイブに誤ったファイル名で保存されたRAPファイルを検出するときに使用する。
+    ///
+    /// # 戻り値
+    ///
+    /// ファイル名から取得した日付、`from_bytes`メソッドでメモリ上のバイト列から読み込んだ場合、
+    /// ファイル名が規則に従っていない場合または観測データが記録されていない場合は`None`
+    pub fn expected_date(&self) -> RapReaderResult<Option<Date>> {
+        let path = match &self.source {
+            RapSource::File { path, .. }
+            | RapSource::Bytes {
+                path: Some(path), ..
+            } => path,
+            RapSource::Bytes { path: None, .. } => return Ok(None),
+        };
+        let Some(expected) = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(parse_rap_filename)
+        else {
+            return Ok(None);
+        };
+        let Some((first, _)) = self.observation_range() else {
+            return Ok(None);
+        };
+        if first.date() != expected {
+            return Err(RapReaderError::FileNameDateMismatch {
+                expected,
+                actual: first.date(),
+            });
+        }
+
+        Ok(Some(expected))
+    }
+}
@@ -0,0 +1,124 @@
+use ::geo::{BoundingRect, Contains, LineString, Point, Polygon};
+use time::PrimitiveDateTime;
+
+use super::{
+    grid_corners, to_degrees, LocationValue, RapReader, RapReaderResult, RapValueIterator,
+};
+
+impl RapReader {
+    /// 引数で指定された日時の観測値のうち、格子の中心がポリゴンの内部にある観測値を返す。
+    ///
+    /// 格子の中心がポリゴンの外接矩形の外部にある場合は、ポリゴンの内外判定を省略する。
+    ///
+    /// # 引数
+    ///
+    /// * `dt` - 観測値を取得する日時
+    /// * `polygon` - 観測値を抽出する範囲を示すポリゴン
+    ///
+    /// # 戻り値
+    ///
+    /// 格子の中心がポリゴンの内部にある観測値
+    pub fn values_within_polygon(
+        &self,
+        dt: PrimitiveDateTime,
+        polygon: &Polygon<f64>,
+    ) -> RapReaderResult<Vec<LocationValue>> {
+        let rect = match polygon.bounding_rect() {
+            Some(rect) => rect,
+            None => return Ok(vec![]),
+        };
+        let (min, max) = (rect.min(), rect.max());
+
+        let mut values = vec![];
+        for lv in self.value_iterator(dt)? {
+            let lv = lv?;
+            if lv.longitude < min.x
+                || max.x < lv.longitude
+                || lv.latitude < min.y
+                || max.y < lv.latitude
+            {
+                continue;
+            }
+            if polygon.contains(&Point::new(lv.longitude, lv.latitude)) {
+                values.push(lv);
+            }
+        }
+
+        Ok(values)
+    }
+}
+
+impl<'a> RapValueIterator<'a> {
+    /// 観測値を格子のポリゴンと組み合わせて返すイテレーターに変換する。
+    ///
+    /// ポリゴンは、`output_csv_with_geom`が出力するWKTと同じ座標で構築する。
+    /// `with_anchor`で座標の格子内の位置を指定した場合も、ポリゴンは格子全体を表現する。
+    pub fn with_geometry(self) -> GeometryIterator<'a> {
+        let grid_width = to_degrees(self.grid_width);
+        let grid_height = to_degrees(self.grid_height);
+
+        GeometryIterator {
+            inner: self,
+            grid_width,
+            grid_height,
+        }
+    }
+}
+
+/// 格子のポリゴンと観測値を順に返すイテレーター
+pub struct GeometryIterator<'a> {
+    /// 観測値を走査するイテレーター
+    inner: RapValueIterator<'a>,
+    /// 格子の幅（度）
+    grid_width: f64,
+    /// 格子の高さ（度）
+    grid_height: f64,
+}
+
+impl<'a> Iterator for GeometryIterator<'a> {
+    type Item = RapReaderResult<(Polygon<f64>, Option<u16>)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let lv = match self.inner.next()? {
+            Ok(lv) => lv,
+            Err(e) => return Some(Err(e)),
+        };
+        // `with_anchor`で格子の中心以外の位置を指定した場合も、格子の中心の座標からポリゴンを構築
+        let (longitude, latitude) = self.inner.anchor.shift_to_center(
+            lv.longitude,
+            lv.latitude,
+            self.grid_width,
+            self.grid_height,
+        );
+        let polygon = grid_polygon(longitude, latitude, self.grid_width, self.grid_height);
+
+        Some(Ok((polygon, lv.value)))
+    }
+}
+
+/// 格子を表現するポリゴンを返す。
+///
+/// # 引数
+///
+/// * `longitude` - 格子の中心の経度（度）
+/// * `latitude` - 格子の中心の緯度（度）
+/// * `width` - 格子の幅（度）
+/// * `height` - 格子の高さ（度）
+///
+/// # 戻り値
+///
+/// 格子を表現するポリゴン
+pub(super) fn grid_polygon(longitude: f64, latitude: f64, width: f64, height: f64) -> Polygon<f64> {
+    let (left, bottom, right, top) = grid_corners(longitude, latitude, width, height);
+
+    // 左上、右上、右下、左下、左上の順にポリゴンの座標を並べる
+    let exterior = LineString::from(vec![
+        (left, top),
+        (right, top),
+        (right, bottom),
+        (left, bottom),
+        (left, top),
+    ]);
+
+    Polygon::new(exterior, vec![])
+}
@@ -0,0 +1,185 @@
+use std::path::Path;
+
+use ::rusqlite::{params, Connection};
+use time::PrimitiveDateTime;
+
+use super::{grid_corners, RapReader, RapReaderError, RapReaderResult};
+
+/// GeoPackageの`application_id`（ASCIIの`GPKG`）
+const GPKG_APPLICATION_ID: i32 = 0x4750_4B47;
+
+/// GeoPackageの`user_version`（バージョン1.3.0）
+const GPKG_USER_VERSION: i32 = 10_300;
+
+/// 格子を記録するフィーチャー・テーブルの名前
+const TABLE_NAME: &str = "rap";
+
+/// 格子の空間参照系（WGS 84）
+const SRS_ID: i32 = 4326;
+
+/// GeoPackageの必須テーブルと格子を記録するフィーチャー・テーブルを作成するSQL
+const CREATE_TABLES: &str = "
+CREATE TABLE gpkg_spatial_ref_sys (
+    srs_name TEXT NOT NULL,
+    srs_id INTEGER NOT NULL PRIMARY KEY,
+    organization TEXT NOT NULL,
+    organization_coordsys_id INTEGER NOT NULL,
+    definition TEXT NOT NULL,
+    description TEXT
+);
+CREATE TABLE gpkg_contents (
+    table_name TEXT NOT NULL PRIMARY KEY,
+    data_type TEXT NOT NULL,
+    identifier TEXT UNIQUE,
+    description TEXT DEFAULT '',
+    last_change DATETIME NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ','now')),
+    min_x DOUBLE,
+    min_y DOUBLE,
+    max_x DOUBLE,
+    max_y DOUBLE,
+    srs_id INTEGER,
+    CONSTRAINT fk_gc_r_srs_id FOREIGN KEY (srs_id) REFERENCES gpkg_spatial_ref_sys(srs_id)
+);
+CREATE TABLE gpkg_geometry_columns (
+    table_name TEXT NOT NULL,
+    column_name TEXT NOT NULL,
+    geometry_type_name TEXT NOT NULL,
+    srs_id INTEGER NOT NULL,
+    z TINYINT NOT NULL,
+    m TINYINT NOT NULL,
+    CONSTRAINT pk_geom_cols PRIMARY KEY (table_name, column_name),
+    CONSTRAINT fk_gc_tn FOREIGN KEY (table_name) REFERENCES gpkg_contents(table_name),
+    CONSTRAINT fk_gc_srs FOREIGN KEY (srs_id) REFERENCES gpkg_spatial_ref_sys (srs_id)
+);
+CREATE TABLE rap (
+    fid INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    geom POLYGON,
+    value INTEGER
+);
+INSERT INTO gpkg_spatial_ref_sys VALUES
+    ('Undefined cartesian SRS', -1, 'NONE', -1, 'undefined', 'undefined cartesian coordinate reference system'),
+    ('Undefined geographic SRS', 0, 'NONE', 0, 'undefined', 'undefined geographic coordinate reference system'),
+    ('WGS 84 geodetic', 4326, 'EPSG', 4326,
+     'GEOGCS[\"WGS 84\",DATUM[\"WGS_1984\",SPHEROID[\"WGS 84\",6378137,298.257223563,AUTHORITY[\"EPSG\",\"7030\"]],AUTHORITY[\"EPSG\",\"6326\"]],PRIMEM[\"Greenwich\",0,AUTHORITY[\"EPSG\",\"8901\"]],UNIT[\"degree\",0.0174532925199433,AUTHORITY[\"EPSG\",\"9122\"]],AUTHORITY[\"EPSG\",\"4326\"]]',
+     'longitude/latitude coordinates in decimal degrees on the WGS 84 spheroid');
+";
+
+/// 引数で指定された日時の観測値を、格子のポリゴンを記録したGeoPackageファイルに出力する。
+///
+/// 格子は`rap`フィーチャー・テーブルに、`output_csv_with_geom`が出力するWKTと同じポリゴンと、
+/// 観測値を記録した`value`属性（欠測値は`NULL`）で記録する。
+/// 空間参照系はWGS 84（SRID 4326）である。
+/// すべての格子を1つのトランザクションで記録する。
+/// 出力するファイルが存在する場合は、ファイルを削除してから出力する。
+///
+/// # 引数
+///
+/// * `reader` - RAPファイル・リーダー
+/// * `dt` - 出力する観測値の日時
+/// * `path` - 出力するGeoPackageファイルのパス
+pub fn output_geopackage(
+    reader: &RapReader,
+    dt: PrimitiveDateTime,
+    path: &Path,
+) -> RapReaderResult<()> {
+    let iterator = reader.value_iterator(dt)?;
+    let width = reader.grid_width_deg();
+    let height = reader.grid_height_deg();
+    let (min_lon, min_lat, max_lon, max_lat) = reader.grid_bounds();
+
+    if path.exists() {
+        std::fs::remove_file(path)?;
+    }
+    let mut conn = Connection::open(path).map_err(write_error)?;
+    conn.pragma_update(None, "application_id", GPKG_APPLICATION_ID)
+        .map_err(write_error)?;
+    conn.pragma_update(None, "user_version", GPKG_USER_VERSION)
+        .map_err(write_error)?;
+
+    let tx = conn.transaction().map_err(write_error)?;
+    tx.execute_batch(CREATE_TABLES).map_err(write_error)?;
+    tx.execute(
+        "INSERT INTO gpkg_contents
+            (table_name, data_type, identifier, description, min_x, min_y, max_x, max_y, srs_id)
+            VALUES (?1, 'features', ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        params![
+            TABLE_NAME,
+            TABLE_NAME,
+            reader.creator_comment(),
+            min_lon,
+            min_lat,
+            max_lon,
+            max_lat,
+            SRS_ID
+        ],
+    )
+    .map_err(write_error)?;
+    tx.execute(
+        "INSERT INTO gpkg_geometry_columns VALUES (?1, 'geom', 'POLYGON', ?2, 0, 0)",
+        params![TABLE_NAME, SRS_ID],
+    )
+    .map_err(write_error)?;
+    {
+        let mut statement = tx
+            .prepare("INSERT INTO rap (geom, value) VALUES (?1, ?2)")
+            .map_err(write_error)?;
+        for lv in iterator {
+            let lv = lv?;
+            let corners = grid_corners(lv.longitude, lv.latitude, width, height);
+            statement
+                .execute(params![polygon_blob(corners), lv.value])
+                .map_err(write_error)?;
+        }
+    }
+    tx.commit().map_err(write_error)?;
+
+    Ok(())
+}
+
+/// 格子のポリゴンをGeoPackageのバイナリ形式で返す。
+///
+/// GeoPackageのヘッダーにはXYの外接矩形を記録し、ジオメトリはリトル・エンディアンのWKBで記録する。
+///
+/// # 引数
+///
+/// * `corners` - `(西端の経度, 南端の緯度, 東端の経度, 北端の緯度)`
+///
+/// # 戻り値
+///
+/// GeoPackageのバイナリ形式で表現したポリゴン
+fn polygon_blob((left, bottom, right, top): (f64, f64, f64, f64)) -> Vec<u8> {
+    let mut blob = Vec::with_capacity(8 + 32 + 9 + 4 + 80);
+
+    // GeoPackageのヘッダー（マジック、バージョン、フラグ、SRID、外接矩形）
+    // フラグは、外接矩形がXY（0b010）、リトル・エンディアン（0b1）であることを示す
+    blob.extend_from_slice(b"GP");
+    blob.push(0);
+    blob.push(0b0000_0011);
+    blob.extend_from_slice(&SRS_ID.to_le_bytes());
+    for v in [left, right, bottom, top] {
+        blob.extend_from_slice(&v.to_le_bytes());
+    }
+
+    // WKBのポリゴン（リトル・エンディアン、ポリゴン、1つのリング、5つの座標）
+    blob.push(1);
+    blob.extend_from_slice(&3u32.to_le_bytes());
+    blob.extend_from_slice(&1u32.to_le_bytes());
+    blob.extend_from_slice(&5u32.to_le_bytes());
+    // 左上、右上、右下、左下、左上の順にポリゴンの座標を並べる
+    for (x, y) in [
+        (left, top),
+        (right, top),
+        (right, bottom),
+        (left, bottom),
+        (left, top),
+    ] {
+        blob.extend_from_slice(&x.to_le_bytes());
+        blob.extend_from_slice(&y.to_le_bytes());
+    }
+
+    blob
+}
+
+fn write_error(e: ::rusqlite::Error) -> RapReaderError {
+    RapReaderError::Write(format!("GeoPackageファイルの出力に失敗しました。{e}"))
+}
@@ -0,0 +1,72 @@
+use time::PrimitiveDateTime;
+
+use super::{LocationValue, RapReader, RapReaderResult, RapValueIterator};
+
+impl RapReader {
+    /// 引数で指定された日時の観測値を走査するビューを返す。
+    ///
+    /// # 引数
+    ///
+    /// * `dt` - 観測値を取得する日時
+    ///
+    /// # 戻り値
+    ///
+    /// 観測値を走査するビュー
+    pub fn grid_view(&self, dt: PrimitiveDateTime) -> RapReaderResult<GridView<'_>> {
+        Ok(GridView {
+            datetime: dt,
+            len: self.grid_cell_count(),
+            bounds: self.grid_bounds(),
+            iterator: self.value_iterator(dt)?,
+        })
+    }
+}
+
+/// ある日時の観測値を走査するビュー
+///
+/// `for`文で観測値を走査できるほか、観測値を展開せずに観測値の数と観測範囲を取得できる。
+pub struct GridView<'a> {
+    /// 観測日時
+    datetime: PrimitiveDateTime,
+    /// 観測値の数
+    len: usize,
+    /// 観測範囲（`(最小経度, 最小緯度, 最大経度, 最大緯度)`）
+    bounds: (f64, f64, f64, f64),
+    /// 観測値を走査するイテレーター
+    iterator: RapValueIterator<'a>,
+}
+
+impl GridView<'_> {
+    /// 観測日時を返す。
+    pub fn datetime(&self) -> PrimitiveDateTime {
+        self.datetime
+    }
+
+    /// 観測値の数（格子数）を返す。
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// 観測値が存在しない場合は`true`を返す。
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// 観測範囲全体の経度と緯度の範囲を度単位で返す。
+    ///
+    /// # 戻り値
+    ///
+    /// `(最小経度, 最小緯度, 最大経度, 最大緯度)`
+    pub fn bounds(&self) -> (f64, f64, f64, f64) {
+        self.bounds
+    }
+}
+
+impl<'a> IntoIterator for GridView<'a> {
+    type Item = RapReaderResult<LocationValue>;
+    type IntoIter = RapValueIterator<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iterator
+    }
+}
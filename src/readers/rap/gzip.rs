@@ -0,0 +1,45 @@
+use std::fs::OpenOptions;
+use std::io::Read;
+use std::path::Path;
+
+use ::flate2::read::GzDecoder;
+
+use super::{RapReaderError, RapReaderResult};
+
+/// gzipのマジックナンバー
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// 引数で指定されたファイルがgzipで圧縮されているか確認する。
+///
+/// 拡張子が`gz`の場合、またはファイルの先頭2バイトがgzipのマジックナンバーの場合に、
+/// gzipで圧縮されていると判断する。
+pub(super) fn is_gzip(path: &Path) -> RapReaderResult<bool> {
+    if path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("gz"))
+    {
+        return Ok(true);
+    }
+    let mut file = OpenOptions::new()
+        .read(true)
+        .open(path)
+        .map_err(|e| RapReaderError::Open(format!("{e}")))?;
+    let mut magic = [0u8; 2];
+    match file.read_exact(&mut magic) {
+        Ok(_) => Ok(magic == GZIP_MAGIC),
+        Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => Ok(false),
+        Err(e) => Err(RapReaderError::Open(format!("{e}"))),
+    }
+}
+
+/// gzipで圧縮されたファイルを、メモリ上に展開する。
+pub(super) fn decompress(path: &Path) -> RapReaderResult<Vec<u8>> {
+    let file = OpenOptions::new()
+        .read(true)
+        .open(path)
+        .map_err(|e| RapReaderError::Open(format!("{e}")))?;
+    let mut bytes = vec![];
+    GzDecoder::new(file).read_to_end(&mut bytes)?;
+
+    Ok(bytes)
+}
@@ -0,0 +1,184 @@
+use std::io::{Cursor, Read, Seek};
+
+use time::PrimitiveDateTime;
+
+use super::{
+    read_comment_part, read_compression_part, read_data_index_entries, read_grid_definition_part,
+    read_level_repetitions_part, CommentPart, CompressionPart, DataIndexPart, GridDefinition,
+    LevelRepetition, LevelRepetitionsPart, ObservationTimes, RapReaderError, RapReaderOptions,
+    RapReaderResult, ReservedRegion,
+};
+
+/// RAPファイルの管理部
+///
+/// `RapReader`と異なり、データ部に移動せずに管理部のみを読み込むため、
+/// 多数のファイルから格子系定義や観測日時などを取得する場合に高速である。
+#[derive(Debug, Clone)]
+pub struct RapHeader {
+    /// コメント
+    pub(super) comment_part: CommentPart,
+    /// データ部へのインデックス
+    ///
+    /// データ部に記録されている圧縮後の大きさ、レーダー運用状況及び解析に使用したアメダスの総数は
+    /// 読み込まない。
+    pub(super) data_index_part: DataIndexPart,
+    /// 格子系定義
+    pub(super) grid_definition: GridDefinition,
+    /// 圧縮方法、観測値表
+    pub(super) compression_part: CompressionPart,
+    /// レベル反復数表
+    pub(super) level_repetitions_part: LevelRepetitionsPart,
+    /// 管理部の予備の領域
+    ///
+    /// 予備の領域を保持しない場合は`None`である。
+    pub(super) reserved_regions: Option<Vec<ReservedRegion>>,
+}
+
+impl RapHeader {
+    /// リーダーから管理部を読み込む。
+    ///
+    /// 引数`reader`の読み込み位置が、RAPファイルの先頭になっていることを想定している。
+    ///
+    /// # 引数
+    ///
+    /// * `reader` - RAPファイルを読み込むリーダー
+    ///
+    /// # 戻り値
+    ///
+    /// `RapHeader`
+    pub fn parse<R>(reader: &mut R) -> RapReaderResult<Self>
+    where
+        R: Read + Seek,
+    {
+        Self::parse_with_options(reader, &RapReaderOptions::default())
+    }
+
+    /// オプションを指定して、リーダーから管理部を読み込む。
+    ///
+    /// # 引数
+    ///
+    /// * `reader` - RAPファイルを読み込むリーダー
+    /// * `options` - RAPファイルを読み込むときのオプション
+    ///
+    /// # 戻り値
+    ///
+    /// `RapHeader`
+    pub fn parse_with_options<R>(
+        reader: &mut R,
+        options: &RapReaderOptions,
+    ) -> RapReaderResult<Self>
+    where
+        R: Read + Seek,
+    {
+        let mut reserved_regions = options.capture_reserved_regions.then(Vec::new);
+        let comment_part = read_comment_part(reader, options)?;
+        let data_index_part = read_data_index_entries(reader, &mut reserved_regions)?;
+        let grid_definition = read_grid_definition_part(reader, &mut reserved_regions)?;
+        let compression_part = read_compression_part(reader, options)?;
+        let level_repetitions_part = read_level_repetitions_part(reader)?;
+
+        Ok(Self {
+            comment_part,
+            data_index_part,
+            grid_definition,
+            compression_part,
+            level_repetitions_part,
+            reserved_regions,
+        })
+    }
+
+    /// 管理部 - コメント - 識別子を返す。
+    pub fn identifier(&self) -> &str {
+        &self.comment_part.identifier
+    }
+
+    /// 管理部 - コメント - 版番号を返す。
+    pub fn version(&self) -> &str {
+        &self.comment_part.version
+    }
+
+    /// 管理部 - コメント - 作成者コメントを返す。
+    pub fn creator_comment(&self) -> &str {
+        &self.comment_part.creator_comment
+    }
+
+    /// 管理部 - データ部へのインデックス - データ数を返す。
+    pub fn number_of_data(&self) -> u32 {
+        self.data_index_part.number_of_data as u32
+    }
+
+    /// 管理部 - データ部へのインデックス - 1日の観測回数を返す。
+    pub fn observation_times(&self) -> ObservationTimes {
+        self.data_index_part.number_of_data
+    }
+
+    /// 管理部 - データ部へのインデックス - 記録されている観測日時を記録順に返す。
+    pub fn observation_date_times(&self) -> impl Iterator<Item = PrimitiveDateTime> + '_ {
+        self.data_index_part
+            .data_properties
+            .iter()
+            .map(|dp| dp.observation_date_time)
+    }
+
+    /// 引数で指定された日時の観測データが記録されているか確認する。
+    ///
+    /// # 引数
+    ///
+    /// * `dt` - 確認する日時
+    ///
+    /// # 戻り値
+    ///
+    /// 観測データが記録されている場合は`true`、記録されていない場合は`false`
+    pub fn contains(&self, dt: PrimitiveDateTime) -> bool {
+        self.observation_date_times().any(|odt| odt == dt)
+    }
+
+    /// 管理部の予備の領域に記録されているバイト列を、ファイル内の位置の順に返す。
+    ///
+    /// `RapReaderOptions::capture_reserved_regions`を`true`にして読み込んだ場合のみ、予備の領域を返す。
+    ///
+    /// # 戻り値
+    ///
+    /// 管理部の予備の領域、予備の領域を保持していない場合は`None`
+    pub fn reserved_regions(&self) -> Option<&[ReservedRegion]> {
+        self.reserved_regions.as_deref()
+    }
+
+    /// 管理部 - 格子系定義を返す。
+    pub fn grid_definition(&self) -> GridDefinition {
+        self.grid_definition
+    }
+
+    /// 管理部 - 圧縮方法、観測値表 - 圧縮方法を返す。
+    pub fn compression_method(&self) -> u16 {
+        self.compression_part.compression_method
+    }
+
+    /// 管理部 - 圧縮方法、観測値表 - レベルの数を返す。
+    pub fn number_of_levels(&self) -> u16 {
+        self.compression_part.number_of_levels
+    }
+
+    /// 管理部 - 圧縮方法、観測値表 - レベル別の観測値を返す。
+    pub fn value_by_levels(&self) -> &[u16] {
+        &self.compression_part.value_by_levels
+    }
+
+    /// 管理部 - レベル、反復数表 - レベルと反復数の組み合わせの数を返す。
+    pub fn number_of_level_repetitions(&self) -> u16 {
+        self.level_repetitions_part.number_of_level_repetitions
+    }
+
+    /// 管理部 - レベル、反復数表 - レベルと反復数の組み合わせを返す。
+    pub fn level_repetitions(&self) -> &[LevelRepetition] {
+        &self.level_repetitions_part.level_repetitions
+    }
+}
+
+impl TryFrom<&[u8]> for RapHeader {
+    type Error = RapReaderError;
+
+    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+        Self::parse(&mut Cursor::new(value))
+    }
+}
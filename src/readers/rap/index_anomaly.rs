@@ -0,0 +1,87 @@
+use std::collections::HashMap;
+
+use time::PrimitiveDateTime;
+
+use super::RapReader;
+
+/// データ部へのインデックスに記録されている観測日時の異常
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum IndexAnomaly {
+    /// 同じ観測日時が複数回記録されている
+    Duplicate {
+        /// 重複した観測日時を記録したデータの、インデックス内の位置
+        index: usize,
+        /// 同じ観測日時を最初に記録したデータの、インデックス内の位置
+        first_index: usize,
+        /// 重複した観測日時
+        datetime: PrimitiveDateTime,
+    },
+    /// 観測日時が直前のデータの観測日時より前である
+    OutOfOrder {
+        /// 観測日時が前に戻ったデータの、インデックス内の位置
+        index: usize,
+        /// 直前のデータの観測日時
+        previous: PrimitiveDateTime,
+        /// 観測日時
+        datetime: PrimitiveDateTime,
+    },
+    /// 直前のデータの観測日時からの間隔が、観測間隔と一致しない
+    Gap {
+        /// 間隔が観測間隔と一致しないデータの、インデックス内の位置
+        index: usize,
+        /// 直前のデータの観測日時
+        previous: PrimitiveDateTime,
+        /// 観測日時
+        datetime: PrimitiveDateTime,
+    },
+}
+
+impl RapReader {
+    /// データ部へのインデックスに記録されている観測日時の、重複、逆順及び間隔の異常を返す。
+    ///
+    /// 観測日時をインデックスに記録されている順に、直前のデータの観測日時と比較する。
+    /// 観測間隔は`observation_times`から求める。
+    /// データ部を読み込まないため、ファイルを結合または修復したときに、
+    /// 観測値を参照する前に誤った観測日時を検出できる。
+    /// データ部の位置と大きさは`verify`で確認する。
+    ///
+    /// # 戻り値
+    ///
+    /// 観測日時の異常を、インデックスに記録されている順に格納した`Vec`（異常がない場合は空）
+    pub fn index_anomalies(&self) -> Vec<IndexAnomaly> {
+        let interval = self.observation_times().interval();
+        let mut first_indexes = HashMap::new();
+        let mut anomalies = vec![];
+        let mut previous: Option<PrimitiveDateTime> = None;
+        for (index, dp) in self.data_properties().iter().enumerate() {
+            let datetime = dp.observation_date_time;
+            if let Some(&first_index) = first_indexes.get(&datetime) {
+                anomalies.push(IndexAnomaly::Duplicate {
+                    index,
+                    first_index,
+                    datetime,
+                });
+            } else {
+                first_indexes.insert(datetime, index);
+                if let Some(previous) = previous {
+                    if datetime < previous {
+                        anomalies.push(IndexAnomaly::OutOfOrder {
+                            index,
+                            previous,
+                            datetime,
+                        });
+                    } else if datetime - previous != interval {
+                        anomalies.push(IndexAnomaly::Gap {
+                            index,
+                            previous,
+                            datetime,
+                        });
+                    }
+                }
+            }
+            previous = Some(datetime);
+        }
+
+        anomalies
+    }
+}
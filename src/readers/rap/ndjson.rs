@@ -0,0 +1,75 @@
+use std::io::Write;
+
+use time::format_description::FormatItem;
+use time::macros::format_description;
+use time::PrimitiveDateTime;
+
+use super::{drive_sink, GridMetadata, GridSink, LocationValue, RapReaderResult, RapValueIterator};
+
+/// NDJSONに出力する観測日時の書式
+const NDJSON_DATETIME_FMT: &[FormatItem<'_>] =
+    format_description!("[year]-[month]-[day]T[hour]:[minute]:[second]");
+
+/// 観測値を、1行に1つの格子を記録したNDJSON（改行区切りのJSON）で出力する。
+///
+/// 格子ごとに`{"t":"2001-04-01T01:00:00","lon":135.0,"lat":36.0,"value":123}`の形式で1行を出力する。
+/// 観測日時は日本標準時であり、欠測値は`null`で出力する。
+/// 観測値を1行ずつ出力するため、すべての観測値をメモリに展開しない。
+/// 観測値の展開に失敗した場合は、出力を中断してエラーを返す。
+///
+/// # 引数
+///
+/// * `writer` - NDJSONの出力先
+/// * `iterator` - 観測値を順に取り出すイテレーター
+/// * `dt` - 観測日時
+pub fn output_ndjson<W>(
+    writer: &mut W,
+    iterator: RapValueIterator,
+    dt: PrimitiveDateTime,
+) -> RapReaderResult<()>
+where
+    W: Write,
+{
+    let (width, height) = iterator.grid_size_deg();
+    let meta = GridMetadata::from_iterator(&iterator, width, height, None);
+    let mut sink = NdjsonSink {
+        writer,
+        datetime: dt.format(NDJSON_DATETIME_FMT).unwrap(),
+    };
+
+    drive_sink(iterator, &meta, &mut sink, |_, _| {})
+}
+
+/// NDJSONを出力する`GridSink`
+struct NdjsonSink<W> {
+    /// NDJSONの出力先
+    writer: W,
+    /// 書式化した観測日時
+    datetime: String,
+}
+
+impl<W> GridSink for NdjsonSink<W>
+where
+    W: Write,
+{
+    fn begin(&mut self, _meta: &GridMetadata) -> std::io::Result<()> {
+        Ok(())
+    }
+
+    fn cell(&mut self, lv: LocationValue) -> std::io::Result<()> {
+        let value = match lv.value {
+            Some(value) => value.to_string(),
+            None => String::from("null"),
+        };
+
+        writeln!(
+            self.writer,
+            r#"{{"t":"{}","lon":{:?},"lat":{:?},"value":{}}}"#,
+            self.datetime, lv.longitude, lv.latitude, value
+        )
+    }
+
+    fn finish(&mut self) -> std::io::Result<()> {
+        self.writer.flush()
+    }
+}
@@ -0,0 +1,140 @@
+use std::path::Path;
+
+use super::{Origin, RapReader, RapReaderError, RapReaderResult, MISSING_VALUE, MM_PER_VALUE};
+
+/// RAPファイルに記録されているすべての観測値を、CF規約に従ったNetCDFファイルに出力する。
+///
+/// 観測値は`precipitation[time, lat, lon]`変数に、0.1mm単位の整数と`scale_factor`で記録する。
+/// 欠測値は`_FillValue`で記録する。
+/// 観測値は日時ごとに出力するため、メモリに展開する観測値は1つの日時分のみである。
+///
+/// # 引数
+///
+/// * `reader` - RAPファイル・リーダー
+/// * `path` - 出力するNetCDFファイルのパス
+pub fn output_netcdf(reader: &RapReader, path: &Path) -> RapReaderResult<()> {
+    output_netcdf_with_origin(reader, path, Origin::TopLeft)
+}
+
+/// 引数で指定された格子の並び順で、すべての観測値をCF規約に従ったNetCDFファイルに出力する。
+///
+/// `Origin::BottomLeft`を指定した場合は、`lat`変数と観測値の行を南から北の順に記録する。
+///
+/// # 引数
+///
+/// * `reader` - RAPファイル・リーダー
+/// * `path` - 出力するNetCDFファイルのパス
+/// * `origin` - 格子の並び順の原点
+pub fn output_netcdf_with_origin(
+    reader: &RapReader,
+    path: &Path,
+    origin: Origin,
+) -> RapReaderResult<()> {
+    let number_of_h_grids = reader.number_of_h_grids() as usize;
+    let number_of_v_grids = reader.number_of_v_grids() as usize;
+    let data_properties = reader.data_properties();
+
+    let mut file = ::netcdf::create(path).map_err(write_error)?;
+    file.add_attribute("Conventions", "CF-1.8")
+        .map_err(write_error)?;
+    file.add_attribute("title", reader.identifier())
+        .map_err(write_error)?;
+    file.add_attribute("source", reader.creator_comment())
+        .map_err(write_error)?;
+    file.add_dimension("time", data_properties.len())
+        .map_err(write_error)?;
+    file.add_dimension("lat", number_of_v_grids)
+        .map_err(write_error)?;
+    file.add_dimension("lon", number_of_h_grids)
+        .map_err(write_error)?;
+
+    // 観測日時（観測値を積算した期間の終了日時）
+    let times = data_properties
+        .iter()
+        .map(|dp| dp.observation_datetime_utc().unix_timestamp() / 60)
+        .collect::<Vec<_>>();
+    let mut variable = file
+        .add_variable::<i64>("time", &["time"])
+        .map_err(write_error)?;
+    variable
+        .put_attribute("standard_name", "time")
+        .map_err(write_error)?;
+    variable
+        .put_attribute("units", "minutes since 1970-01-01 00:00:00 UTC")
+        .map_err(write_error)?;
+    variable
+        .put_attribute("calendar", "standard")
+        .map_err(write_error)?;
+    variable.put_values(&times, ..).map_err(write_error)?;
+
+    // 格子の中心の緯度（原点が北西端の場合は北から南の順、南西端の場合は南から北の順）
+    let start_latitude = reader.grid_start_latitude_deg();
+    let grid_height = reader.grid_height_deg();
+    let mut latitudes = (0..number_of_v_grids)
+        .map(|i| start_latitude - grid_height * i as f64)
+        .collect::<Vec<_>>();
+    origin.reorder(&mut latitudes, 1);
+    let mut variable = file
+        .add_variable::<f64>("lat", &["lat"])
+        .map_err(write_error)?;
+    variable
+        .put_attribute("standard_name", "latitude")
+        .map_err(write_error)?;
+    variable
+        .put_attribute("units", "degrees_north")
+        .map_err(write_error)?;
+    variable.put_values(&latitudes, ..).map_err(write_error)?;
+
+    // 格子の中心の経度（西から東の順）
+    let start_longitude = reader.grid_start_longitude_deg();
+    let grid_width = reader.grid_width_deg();
+    let longitudes = (0..number_of_h_grids)
+        .map(|i| start_longitude + grid_width * i as f64)
+        .collect::<Vec<_>>();
+    let mut variable = file
+        .add_variable::<f64>("lon", &["lon"])
+        .map_err(write_error)?;
+    variable
+        .put_attribute("standard_name", "longitude")
+        .map_err(write_error)?;
+    variable
+        .put_attribute("units", "degrees_east")
+        .map_err(write_error)?;
+    variable.put_values(&longitudes, ..).map_err(write_error)?;
+
+    // 観測値
+    let mut variable = file
+        .add_variable::<u16>("precipitation", &["time", "lat", "lon"])
+        .map_err(write_error)?;
+    variable
+        .set_fill_value(MISSING_VALUE)
+        .map_err(write_error)?;
+    variable
+        .put_attribute("standard_name", "precipitation_amount")
+        .map_err(write_error)?;
+    variable.put_attribute("units", "mm").map_err(write_error)?;
+    variable
+        .put_attribute("scale_factor", MM_PER_VALUE as f32)
+        .map_err(write_error)?;
+    variable
+        .put_attribute("cell_methods", "time: sum")
+        .map_err(write_error)?;
+    for (index, dp) in data_properties.iter().enumerate() {
+        let values = reader
+            .to_grid_with_origin(dp.observation_date_time, origin)?
+            .into_iter()
+            .map(|value| value.unwrap_or(MISSING_VALUE))
+            .collect::<Vec<_>>();
+        variable
+            .put_values(&values, (index, .., ..))
+            .map_err(write_error)?;
+    }
+
+    file.close().map_err(write_error)?;
+
+    Ok(())
+}
+
+fn write_error(e: ::netcdf::Error) -> RapReaderError {
+    RapReaderError::Write(format!("NetCDFファイルの出力に失敗しました。{e}"))
+}
@@ -0,0 +1,194 @@
+use std::fs::OpenOptions;
+use std::path::Path;
+use std::sync::Arc;
+
+use ::parquet::data_type::{DoubleType, Int32Type, Int64Type};
+use ::parquet::file::metadata::KeyValue;
+use ::parquet::file::properties::WriterProperties;
+use ::parquet::file::writer::SerializedFileWriter;
+use ::parquet::schema::parser::parse_message_type;
+use time::PrimitiveDateTime;
+
+use super::{ExportOptions, RapReader, RapReaderError, RapReaderResult};
+
+/// Parquetファイルのスキーマ
+///
+/// 観測日時は、RAPファイルに記録されている日本標準時をそのまま記録するため、
+/// UTCに調整されていないタイムスタンプとする。
+const PARQUET_SCHEMA: &str = "
+message rap {
+    REQUIRED DOUBLE longitude;
+    REQUIRED DOUBLE latitude;
+    OPTIONAL INT32 value;
+    REQUIRED INT64 observation_time (TIMESTAMP(MILLIS, false));
+}
+";
+
+/// 1つの行グループに記録する行数
+const ROW_GROUP_SIZE: usize = 65_536;
+
+/// 1つの行グループに記録する列の値
+#[derive(Default)]
+struct RowGroupBuffer {
+    /// 経度（度）
+    longitudes: Vec<f64>,
+    /// 緯度（度）
+    latitudes: Vec<f64>,
+    /// 欠測値を除いた観測値
+    values: Vec<i32>,
+    /// 観測値の定義レベル（欠測値は0、それ以外は1）
+    value_def_levels: Vec<i16>,
+}
+
+impl RowGroupBuffer {
+    fn len(&self) -> usize {
+        self.longitudes.len()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.longitudes.is_empty()
+    }
+
+    fn clear(&mut self) {
+        self.longitudes.clear();
+        self.latitudes.clear();
+        self.values.clear();
+        self.value_def_levels.clear();
+    }
+}
+
+/// 引数で指定された日時の観測値をParquetファイルに出力する。
+///
+/// 観測値は`RapValueIterator`から順に読み込み、一定の行数ごとに行グループとして出力するため、
+/// すべての観測値をメモリに展開しない。
+///
+/// # 引数
+///
+/// * `reader` - RAPファイル・リーダー
+/// * `dt` - 出力する観測値の日時
+/// * `path` - 出力するParquetファイルのパス
+pub fn output_parquet(
+    reader: &RapReader,
+    dt: PrimitiveDateTime,
+    path: &Path,
+) -> RapReaderResult<()> {
+    output_parquet_with_options(reader, dt, path, &ExportOptions::default())
+}
+
+/// オプションに従って、引数で指定された日時の観測値をParquetファイルに出力する。
+///
+/// # 引数
+///
+/// * `reader` - RAPファイル・リーダー
+/// * `dt` - 出力する観測値の日時
+/// * `path` - 出力するParquetファイルのパス
+/// * `options` - 観測値を出力するときのオプション
+pub fn output_parquet_with_options(
+    reader: &RapReader,
+    dt: PrimitiveDateTime,
+    path: &Path,
+    options: &ExportOptions,
+) -> RapReaderResult<()> {
+    let iterator = reader.value_iterator(dt)?;
+    let iterator_anchor = iterator.anchor;
+    let (width, height) = iterator.grid_size_deg();
+    let schema = Arc::new(parse_message_type(PARQUET_SCHEMA).map_err(write_error)?);
+    let key_value_metadata = options
+        .metadata_entries()
+        .into_iter()
+        .map(|(key, value)| KeyValue::new(key.to_string(), value))
+        .collect::<Vec<_>>();
+    let props = Arc::new(
+        WriterProperties::builder()
+            .set_key_value_metadata((!key_value_metadata.is_empty()).then_some(key_value_metadata))
+            .build(),
+    );
+    let file = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(path)?;
+    let mut writer = SerializedFileWriter::new(file, schema, props).map_err(write_error)?;
+    let observation_time = dt.assume_utc().unix_timestamp() * 1_000;
+
+    let mut buffer = RowGroupBuffer::default();
+    for lv in iterator {
+        let lv = lv?;
+        if options.skips(lv.value) {
+            continue;
+        }
+        let (_, (longitude, latitude)) = options.anchored(iterator_anchor, &lv, width, height);
+        buffer.longitudes.push(longitude);
+        buffer.latitudes.push(latitude);
+        match lv.value {
+            Some(value) => {
+                buffer.values.push(value as i32);
+                buffer.value_def_levels.push(1);
+            }
+            None => buffer.value_def_levels.push(0),
+        }
+        if ROW_GROUP_SIZE <= buffer.len() {
+            write_row_group(&mut writer, &buffer, observation_time)?;
+            buffer.clear();
+        }
+    }
+    if !buffer.is_empty() {
+        write_row_group(&mut writer, &buffer, observation_time)?;
+    }
+    writer.close().map_err(write_error)?;
+
+    Ok(())
+}
+
+/// 行グループを出力する。
+fn write_row_group<W>(
+    writer: &mut SerializedFileWriter<W>,
+    buffer: &RowGroupBuffer,
+    observation_time: i64,
+) -> RapReaderResult<()>
+where
+    W: std::io::Write + Send,
+{
+    let mut row_group = writer.next_row_group().map_err(write_error)?;
+
+    // 経度
+    let mut column = row_group.next_column().map_err(write_error)?.unwrap();
+    column
+        .typed::<DoubleType>()
+        .write_batch(&buffer.longitudes, None, None)
+        .map_err(write_error)?;
+    column.close().map_err(write_error)?;
+
+    // 緯度
+    let mut column = row_group.next_column().map_err(write_error)?.unwrap();
+    column
+        .typed::<DoubleType>()
+        .write_batch(&buffer.latitudes, None, None)
+        .map_err(write_error)?;
+    column.close().map_err(write_error)?;
+
+    // 観測値
+    let mut column = row_group.next_column().map_err(write_error)?.unwrap();
+    column
+        .typed::<Int32Type>()
+        .write_batch(&buffer.values, Some(&buffer.value_def_levels), None)
+        .map_err(write_error)?;
+    column.close().map_err(write_error)?;
+
+    // 観測日時
+    let observation_times = vec![observation_time; buffer.len()];
+    let mut column = row_group.next_column().map_err(write_error)?.unwrap();
+    column
+        .typed::<Int64Type>()
+        .write_batch(&observation_times, None, None)
+        .map_err(write_error)?;
+    column.close().map_err(write_error)?;
+
+    row_group.close().map_err(write_error)?;
+
+    Ok(())
+}
+
+fn write_error(e: ::parquet::errors::ParquetError) -> RapReaderError {
+    RapReaderError::Write(format!("Parquetファイルの出力に失敗しました。{e}"))
+}
@@ -0,0 +1,70 @@
+use super::{is_missing, LocationValue, MM_PER_VALUE};
+
+/// 降水量
+///
+/// RAPファイルに記録されている0.1mm単位の観測値を保持する。
+/// 欠測値は`Rainfall`で表現せず、`Option<Rainfall>`の`None`で表現する。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Rainfall(u16);
+
+impl Rainfall {
+    /// RAPファイルに記録されている観測値から降水量を構築する。
+    ///
+    /// # 引数
+    ///
+    /// * `value` - 0.1mm単位の観測値
+    ///
+    /// # 戻り値
+    ///
+    /// 降水量、観測値が欠測値の場合は`None`
+    pub fn new(value: u16) -> Option<Self> {
+        if is_missing(value) {
+            None
+        } else {
+            Some(Self(value))
+        }
+    }
+
+    /// 降水量をmm単位で返す。
+    pub fn mm(&self) -> f64 {
+        self.0 as f64 * MM_PER_VALUE
+    }
+
+    /// 降水量を0.1mm単位で返す。
+    pub fn tenths_mm(&self) -> u16 {
+        self.0
+    }
+}
+
+/// 0.1mm単位の値から降水量を構築する。
+///
+/// 欠測値を判定しないため、`u16::MAX`は6553.5mmの降水量になる。
+/// RAPファイルから読み込んだ観測値は、欠測値を`None`に変換する`Rainfall::new`で構築する。
+impl From<u16> for Rainfall {
+    fn from(value: u16) -> Self {
+        Self(value)
+    }
+}
+
+impl From<Rainfall> for u16 {
+    fn from(value: Rainfall) -> Self {
+        value.0
+    }
+}
+
+impl std::fmt::Display for Rainfall {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{} mm", self.0 / 10, self.0 % 10)
+    }
+}
+
+impl LocationValue {
+    /// 観測値を降水量で返す。
+    ///
+    /// # 戻り値
+    ///
+    /// 降水量、欠測値の場合は`None`
+    pub fn rainfall(&self) -> Option<Rainfall> {
+        self.value.and_then(Rainfall::new)
+    }
+}
@@ -0,0 +1,207 @@
+use std::io::Write;
+
+use time::PrimitiveDateTime;
+
+use super::{
+    grid_wkt, CoordinateAnchor, DataProperty, ExportOptions, LocationValue, RapReader,
+    RapReaderResult, RapValueIterator,
+};
+
+/// 観測値を出力する前に`GridSink`に渡す、出力する観測値の情報
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GridMetadata {
+    /// 出力する観測データの属性
+    ///
+    /// 観測データの属性が不明な場合は`None`である。
+    pub data_property: Option<DataProperty>,
+    /// 格子の幅（度）
+    pub grid_width: f64,
+    /// 格子の高さ（度）
+    pub grid_height: f64,
+    /// 経度方向の格子数
+    pub number_of_h_grids: u16,
+    /// 緯度方向の格子数
+    ///
+    /// `RapValueIterator::new`で構築したイテレーターから出力する場合は`None`である。
+    pub number_of_v_grids: Option<u16>,
+    /// `GridSink::cell`に渡す座標の、格子内の位置
+    pub anchor: CoordinateAnchor,
+}
+
+impl GridMetadata {
+    /// イテレーターから、出力する観測値の情報を構築する。
+    pub(super) fn from_iterator(
+        iterator: &RapValueIterator,
+        grid_width: f64,
+        grid_height: f64,
+        data_property: Option<DataProperty>,
+    ) -> Self {
+        Self {
+            data_property,
+            grid_width,
+            grid_height,
+            number_of_h_grids: iterator.number_of_h_grids,
+            number_of_v_grids: iterator.number_of_v_grids,
+            anchor: iterator.anchor,
+        }
+    }
+}
+
+/// 観測値の出力先
+///
+/// `RapReader::export`メソッドは、観測値を出力する前に`begin`を、観測値を1つ展開するたびに`cell`を、
+/// すべての観測値を出力した後に`finish`を呼び出す。
+/// データベースや独自のバイナリ形式など、組み込みの出力関数がない形式で出力するときに実装する。
+pub trait GridSink {
+    /// 観測値の出力を開始する。
+    ///
+    /// # 引数
+    ///
+    /// * `meta` - 出力する観測値の情報
+    fn begin(&mut self, meta: &GridMetadata) -> std::io::Result<()>;
+
+    /// 観測値を1つ出力する。
+    ///
+    /// # 引数
+    ///
+    /// * `lv` - 出力する座標と観測値
+    fn cell(&mut self, lv: LocationValue) -> std::io::Result<()>;
+
+    /// 観測値の出力を終了する。
+    fn finish(&mut self) -> std::io::Result<()>;
+}
+
+impl RapReader {
+    /// 引数で指定された日時の観測値を、`GridSink`に出力する。
+    ///
+    /// # 引数
+    ///
+    /// * `dt` - 出力する観測値の日時
+    /// * `sink` - 観測値の出力先
+    pub fn export<S>(&self, dt: PrimitiveDateTime, sink: &mut S) -> RapReaderResult<()>
+    where
+        S: GridSink + ?Sized,
+    {
+        let data_property = *self.resolve_data_property(dt)?;
+        let iterator = self.value_iterator(dt)?;
+        let meta = GridMetadata::from_iterator(
+            &iterator,
+            self.grid_width_deg(),
+            self.grid_height_deg(),
+            Some(data_property),
+        );
+        sink.begin(&meta)?;
+        for lv in iterator {
+            sink.cell(lv?)?;
+        }
+        sink.finish()?;
+
+        Ok(())
+    }
+}
+
+/// ジオメトリ付きCSVファイルを出力する`GridSink`
+///
+/// `output_csv_with_geom`と同じ形式で出力する。
+pub struct CsvWithGeomSink<W> {
+    /// CSVファイルの出力先
+    writer: W,
+    /// 観測値を出力するときのオプション
+    options: ExportOptions,
+    /// 格子の幅と高さ（度）
+    grid_size: (f64, f64),
+    /// `cell`に渡される座標の、格子内の位置
+    iterator_anchor: CoordinateAnchor,
+}
+
+impl<W> CsvWithGeomSink<W>
+where
+    W: Write,
+{
+    /// ジオメトリ付きCSVファイルを出力する`GridSink`を構築する。
+    ///
+    /// # 引数
+    ///
+    /// * `writer` - CSVファイルの出力先
+    /// * `options` - 観測値を出力するときのオプション
+    ///
+    /// # 戻り値
+    ///
+    /// `CsvWithGeomSink`
+    pub fn new(writer: W, options: ExportOptions) -> Self {
+        Self {
+            writer,
+            options,
+            grid_size: (0.0, 0.0),
+            iterator_anchor: CoordinateAnchor::Center,
+        }
+    }
+}
+
+impl<W> GridSink for CsvWithGeomSink<W>
+where
+    W: Write,
+{
+    fn begin(&mut self, meta: &GridMetadata) -> std::io::Result<()> {
+        self.grid_size = (meta.grid_width, meta.grid_height);
+        self.iterator_anchor = meta.anchor;
+        self.options.write_metadata_comment(&mut self.writer)?;
+
+        writeln!(self.writer, "longitude,latitude,value,geom")
+    }
+
+    fn cell(&mut self, lv: LocationValue) -> std::io::Result<()> {
+        if self.options.skips(lv.value) {
+            return Ok(());
+        }
+        let (grid_width, grid_height) = self.grid_size;
+        let precision = self.options.precision;
+        let value_str = self.options.format_value(lv.value);
+        let ((center_lon, center_lat), (lon, lat)) =
+            self.options
+                .anchored(self.iterator_anchor, &lv, grid_width, grid_height);
+        let wkt = grid_wkt(center_lon, center_lat, grid_width, grid_height, precision);
+
+        writeln!(
+            self.writer,
+            "{:.*},{:.*},{},\"{}\"",
+            precision, lon, precision, lat, value_str, wkt
+        )
+    }
+
+    fn finish(&mut self) -> std::io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+/// 観測値を1つずつ`GridSink`に出力する。
+///
+/// イテレーターがエラーを返した場合は、`finish`を呼び出さずにエラーを返す。
+///
+/// # 引数
+///
+/// * `iterator` - 観測値を順に取り出すイテレーター
+/// * `meta` - 出力する観測値の情報
+/// * `sink` - 観測値の出力先
+/// * `progress` - 観測値を1つ処理するたびに、処理した観測値の数と観測値の総数を受け取るクロージャー
+///   （観測値の総数が不明な場合は0）
+pub(super) fn drive_sink<S, F>(
+    iterator: RapValueIterator,
+    meta: &GridMetadata,
+    sink: &mut S,
+    mut progress: F,
+) -> RapReaderResult<()>
+where
+    S: GridSink + ?Sized,
+    F: FnMut(usize, usize),
+{
+    let total = iterator.number_of_cells().unwrap_or(0);
+    sink.begin(meta)?;
+    for (index, lv) in iterator.enumerate() {
+        progress(index + 1, total);
+        sink.cell(lv?)?;
+    }
+    sink.finish()?;
+
+    Ok(())
+}
@@ -0,0 +1,269 @@
+use std::collections::HashMap;
+
+use time::PrimitiveDateTime;
+
+use super::decode::RunLengthKind;
+use super::{is_missing, LocationValue, RapReader, RapReaderResult};
+
+/// レベルごとの格子数
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LevelHistogram {
+    /// レベルごとの格子数
+    ///
+    /// インデックスがレベルを示す。観測値が欠測値のレベルの格子数は0である。
+    pub counts: Vec<usize>,
+    /// 欠測値の格子数
+    pub missing: usize,
+}
+
+/// ランレングス圧縮の種類ごとの格子数
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RunLengthBreakdown {
+    /// レベル反復表によるランレングス圧縮(a)で記録された格子数
+    pub level_repetition: usize,
+    /// レベル反復表によらないランレングス圧縮(b)で記録された格子数
+    pub repetition: usize,
+    /// 頻度が多い単独のレベル値(c)で記録された格子数
+    pub frequent_level: usize,
+    /// 頻度が少ない単独のレベル値(d)で記録された格子数
+    pub infrequent_level: usize,
+}
+
+impl RapReader {
+    /// 引数で指定された日時の観測値のうち、観測値が最大の格子を返す。
+    ///
+    /// 欠測値の格子は無視する。観測値が最大の格子が複数ある場合は、最初に記録されている格子を返す。
+    ///
+    /// # 引数
+    ///
+    /// * `dt` - 観測値を取得する日時
+    ///
+    /// # 戻り値
+    ///
+    /// 観測値が最大の格子の座標と観測値、すべての格子が欠測値の場合は`None`
+    pub fn max_cell(&self, dt: PrimitiveDateTime) -> RapReaderResult<Option<LocationValue>> {
+        let mut max: Option<LocationValue> = None;
+        for lv in self.value_iterator(dt)? {
+            let lv = lv?;
+            let value = match lv.value {
+                Some(value) => value,
+                None => continue,
+            };
+            if max.as_ref().is_none_or(|m| m.value < Some(value)) {
+                max = Some(lv);
+            }
+        }
+
+        Ok(max)
+    }
+
+    /// 引数で指定された日時の観測値を、レベルごとに数える。
+    ///
+    /// 観測値を格子に展開せずに、ランレングス圧縮されたレベルと反復数から数える。
+    ///
+    /// # 引数
+    ///
+    /// * `dt` - 観測値を数える日時
+    ///
+    /// # 戻り値
+    ///
+    /// レベルごとの格子数と欠測値の格子数
+    pub fn level_histogram(&self, dt: PrimitiveDateTime) -> RapReaderResult<LevelHistogram> {
+        let mut histogram = LevelHistogram {
+            counts: vec![0; self.value_by_levels().len()],
+            missing: 0,
+        };
+        let mut iterator = self.value_iterator(dt)?;
+        while let Some(ev) = iterator.next_run() {
+            let ev = ev?;
+            let count = ev.number_of_repetitions as usize;
+            if is_missing(ev.value) {
+                histogram.missing += count;
+            } else {
+                histogram.counts[ev.level as usize] += count;
+            }
+        }
+
+        Ok(histogram)
+    }
+
+    /// 引数で指定された日時に、観測値が閾値を超える格子があるか確認する。
+    ///
+    /// 観測値を格子に展開せずにランレングス圧縮されたレベルを走査して、
+    /// 観測値が閾値を超える格子を見つけた時点で走査を終了する。
+    /// 欠測値の格子は無視する。
+    ///
+    /// # 引数
+    ///
+    /// * `dt` - 観測値を確認する日時
+    /// * `threshold` - 閾値
+    ///
+    /// # 戻り値
+    ///
+    /// 観測値が閾値を超える格子がある場合は`true`
+    pub fn has_precipitation(
+        &self,
+        dt: PrimitiveDateTime,
+        threshold: u16,
+    ) -> RapReaderResult<bool> {
+        let mut iterator = self.value_iterator(dt)?;
+        while let Some(ev) = iterator.next_run() {
+            let ev = ev?;
+            if !is_missing(ev.value) && threshold < ev.value {
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    }
+
+    /// 引数で指定された日時の圧縮データの圧縮率を返す。
+    ///
+    /// 圧縮しない場合は1格子あたり2バイトであるため、格子数の2倍を圧縮データのバイト数で割った値を返す。
+    ///
+    /// # 引数
+    ///
+    /// * `dt` - 圧縮率を取得する日時
+    ///
+    /// # 戻り値
+    ///
+    /// 圧縮率
+    pub fn compression_ratio(&self, dt: PrimitiveDateTime) -> RapReaderResult<f64> {
+        let dp = self.resolve_data_property(dt)?;
+
+        Ok((self.grid_cell_count() * 2) as f64 / dp.compressed_data_size as f64)
+    }
+
+    /// 引数で指定された日時の観測値を、ランレングス圧縮の種類ごとに数える。
+    ///
+    /// # 引数
+    ///
+    /// * `dt` - 観測値を数える日時
+    ///
+    /// # 戻り値
+    ///
+    /// ランレングス圧縮の種類ごとの格子数
+    pub fn run_length_breakdown(
+        &self,
+        dt: PrimitiveDateTime,
+    ) -> RapReaderResult<RunLengthBreakdown> {
+        let mut breakdown = RunLengthBreakdown::default();
+        let mut iterator = self.value_iterator(dt)?;
+        while let Some(ev) = iterator.next_run() {
+            let ev = ev?;
+            let count = match ev.kind {
+                RunLengthKind::LevelRepetition => &mut breakdown.level_repetition,
+                RunLengthKind::Repetition => &mut breakdown.repetition,
+                RunLengthKind::FrequentLevel => &mut breakdown.frequent_level,
+                RunLengthKind::InfrequentLevel => &mut breakdown.infrequent_level,
+            };
+            *count += ev.number_of_repetitions as usize;
+        }
+
+        Ok(breakdown)
+    }
+
+    /// 引数で指定された日時の観測値が欠測値の格子を、ビット列で返す。
+    ///
+    /// 最北西端の格子から経度方向、緯度方向の優先順位で、記録順に`i`番目の格子を
+    /// `i / 8`バイト目の下位から`i % 8`番目のビットで表現する。
+    /// 欠測値の格子のビットは1、それ以外の格子のビットは0である。
+    /// 格子数が8の倍数でない場合、最後のバイトの余ったビットは0である。
+    ///
+    /// # 引数
+    ///
+    /// * `dt` - 観測値を取得する日時
+    ///
+    /// # 戻り値
+    ///
+    /// 格子数を8で割って切り上げたバイト数のビット列
+    pub fn missing_mask(&self, dt: PrimitiveDateTime) -> RapReaderResult<Vec<u8>> {
+        let number_of_cells = self.grid_cell_count();
+        let mut mask = vec![0u8; number_of_cells.div_ceil(8)];
+        let mut index = 0;
+        let mut iterator = self.value_iterator(dt)?;
+        while let Some(ev) = iterator.next_run() {
+            let ev = ev?;
+            let end = (index + ev.number_of_repetitions as usize).min(number_of_cells);
+            if is_missing(ev.value) {
+                for i in index..end {
+                    mask[i / 8] |= 1 << (i % 8);
+                }
+            }
+            index = end;
+        }
+
+        Ok(mask)
+    }
+
+    /// 引数で指定された日時の観測値のうち、観測値が最小値以上の格子のみを返す。
+    ///
+    /// 観測値を格子に展開せずに、ランレングス圧縮されたレベルと反復数から格子を求める。
+    /// 欠測値の格子は含めない。`min_value`に1を指定すると、雨が降った格子のみを返す。
+    ///
+    /// # 引数
+    ///
+    /// * `dt` - 観測値を取得する日時
+    /// * `min_value` - 返す格子の観測値の最小値
+    ///
+    /// # 戻り値
+    ///
+    /// `(北からの行のインデックス, 西からの列のインデックス)`をキー、観測値を値とした`HashMap`
+    pub fn sparse_cells(
+        &self,
+        dt: PrimitiveDateTime,
+        min_value: u16,
+    ) -> RapReaderResult<HashMap<(u16, u16), u16>> {
+        let number_of_cells = self.grid_cell_count();
+        let number_of_h_grids = self.number_of_h_grids() as usize;
+        let mut cells = HashMap::new();
+        let mut index = 0;
+        let mut iterator = self.value_iterator(dt)?;
+        while let Some(ev) = iterator.next_run() {
+            let ev = ev?;
+            let end = (index + ev.number_of_repetitions as usize).min(number_of_cells);
+            if !is_missing(ev.value) && min_value <= ev.value {
+                for i in index..end {
+                    let row = (i / number_of_h_grids) as u16;
+                    let col = (i % number_of_h_grids) as u16;
+                    cells.insert((row, col), ev.value);
+                }
+            }
+            index = end;
+        }
+
+        Ok(cells)
+    }
+}
+
+/// 2つの日時の観測値の差を格子ごとに返す。
+///
+/// `b`の観測値から`a`の観測値を引いた値を返す。
+/// 2つのRAPファイルの格子系定義は一致している必要がある。
+///
+/// # 引数
+///
+/// * `a` - 引く観測値を記録したRAPファイル・リーダー
+/// * `a_dt` - 引く観測値の日時
+/// * `b` - 引かれる観測値を記録したRAPファイル・リーダー
+/// * `b_dt` - 引かれる観測値の日時
+///
+/// # 戻り値
+///
+/// 格子ごとの観測値の差を記録順に格納した`Vec`（どちらかの観測値が欠測値の格子は`None`）
+pub fn diff_grids(
+    a: &RapReader,
+    a_dt: PrimitiveDateTime,
+    b: &RapReader,
+    b_dt: PrimitiveDateTime,
+) -> RapReaderResult<Vec<Option<i32>>> {
+    a.ensure_grid_definition_matches(b)?;
+    let a_values = a.to_grid(a_dt)?;
+    let b_values = b.to_grid(b_dt)?;
+
+    Ok(a_values
+        .into_iter()
+        .zip(b_values)
+        .map(|(a, b)| Some(b? as i32 - a? as i32))
+        .collect())
+}
@@ -0,0 +1,190 @@
+use std::io::{Read, Seek, SeekFrom};
+
+use super::{
+    decode_grid_into, read_bytes, read_u32, read_u64, CompressionMethod, DataProperty, RapHeader,
+    RapReaderError, RapReaderOptions, RapReaderResult,
+};
+
+/// シークできないリーダーから、RAPファイルを先頭から順に読み込むリーダー
+///
+/// 管理部を読み込んだ後、データ部をファイルに記録されている順に読み込んで、観測値を展開する。
+/// 読み込み位置を戻さないため、gzipの展開器やネットワーク・ストリームなど、
+/// シークできないリーダーからも観測値を読み込める。
+/// データ部に記録されている圧縮後の大きさ、レーダー運用状況及び解析に使用したアメダスの総数は、
+/// データ部を読み込んだときに取得する。
+pub struct RapStreamReader<R> {
+    /// 読み込み位置を前方にのみ移動するリーダー
+    reader: ForwardReader<R>,
+    /// 管理部
+    header: RapHeader,
+    /// データ部へのインデックスのインデックスを、データ部の開始位置の順に並べた`Vec`
+    order: Vec<usize>,
+    /// 次に読み込むデータ部の、`order`のインデックス
+    next: usize,
+}
+
+impl<R> RapStreamReader<R>
+where
+    R: Read,
+{
+    /// リーダーから管理部を読み込む。
+    ///
+    /// 引数`reader`の読み込み位置が、RAPファイルの先頭になっていることを想定している。
+    ///
+    /// # 引数
+    ///
+    /// * `reader` - RAPファイルを読み込むリーダー
+    ///
+    /// # 戻り値
+    ///
+    /// `RapStreamReader`
+    pub fn new(reader: R) -> RapReaderResult<Self> {
+        Self::with_options(reader, RapReaderOptions::default())
+    }
+
+    /// オプションを指定して、リーダーから管理部を読み込む。
+    ///
+    /// `RapReaderOptions::buffer_capacity`は使用しない。
+    ///
+    /// # 引数
+    ///
+    /// * `reader` - RAPファイルを読み込むリーダー
+    /// * `options` - RAPファイルを読み込むときのオプション
+    ///
+    /// # 戻り値
+    ///
+    /// `RapStreamReader`
+    pub fn with_options(reader: R, options: RapReaderOptions) -> RapReaderResult<Self> {
+        let mut reader = ForwardReader {
+            inner: reader,
+            position: 0,
+        };
+        let header = RapHeader::parse_with_options(&mut reader, &options)?;
+        if let CompressionMethod::Other(method) =
+            CompressionMethod::from(header.compression_method())
+        {
+            return Err(RapReaderError::CompressionMethodUnsupported(method));
+        }
+        let data_properties = &header.data_index_part.data_properties;
+        let mut order = (0..data_properties.len()).collect::<Vec<_>>();
+        order.sort_by_key(|&index| data_properties[index].data_start_position);
+
+        Ok(Self {
+            reader,
+            header,
+            order,
+            next: 0,
+        })
+    }
+
+    /// 管理部を返す。
+    ///
+    /// データ部へのインデックスには、データ部に記録されている圧縮後の大きさ、
+    /// レーダー運用状況及び解析に使用したアメダスの総数は記録されていない。
+    pub fn header(&self) -> &RapHeader {
+        &self.header
+    }
+
+    /// 次のデータ部を読み込んで、観測データの属性と展開した観測値を返す。
+    fn read_next_block(
+        &mut self,
+        index: usize,
+    ) -> RapReaderResult<(DataProperty, Vec<Option<u16>>)> {
+        let mut dp = self.header.data_index_part.data_properties[index];
+        let reader = &mut self.reader;
+        reader.seek(SeekFrom::Start(dp.data_start_position as u64))?;
+        dp.compressed_data_size = read_u32(reader, "データ部の圧縮後の大きさ")?;
+        let compressed = read_bytes(reader, dp.compressed_data_size as usize, "圧縮データ")?;
+        dp.radar_operation_statuses = read_u64(reader, "データ部のレーダー運用状況")?;
+        dp.number_of_amedas = read_u32(reader, "データ部の解析に使用したアメダスの総数")?;
+        if compressed.is_empty() {
+            return Err(RapReaderError::EmptyBlock(dp.observation_date_time));
+        }
+
+        let gd = self.header.grid_definition();
+        let mut values = Vec::new();
+        decode_grid_into(
+            &compressed,
+            self.header.value_by_levels(),
+            self.header.level_repetitions(),
+            gd.number_of_h_grids,
+            gd.number_of_v_grids,
+            &mut values,
+        )
+        .map_err(RapReaderError::from)?;
+
+        Ok((dp, values))
+    }
+}
+
+impl<R> Iterator for RapStreamReader<R>
+where
+    R: Read,
+{
+    type Item = RapReaderResult<(DataProperty, Vec<Option<u16>>)>;
+
+    /// 次のデータ部の観測データの属性と、記録順に格納した観測値（欠測値は`None`）を返す。
+    ///
+    /// データ部は、ファイルに記録されている順に返す。
+    fn next(&mut self) -> Option<Self::Item> {
+        let index = *self.order.get(self.next)?;
+        self.next += 1;
+
+        Some(self.read_next_block(index))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.order.len() - self.next;
+
+        (remaining, Some(remaining))
+    }
+}
+
+/// 読み込み位置を前方にのみ移動するリーダー
+///
+/// 前方へのシークは、読み込んだバイト列を捨てることで実現する。
+/// 後方へのシークはエラーを返す。
+struct ForwardReader<R> {
+    /// 元のリーダー
+    inner: R,
+    /// 先頭からの読み込み位置
+    position: u64,
+}
+
+impl<R> Read for ForwardReader<R>
+where
+    R: Read,
+{
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.position += n as u64;
+
+        Ok(n)
+    }
+}
+
+impl<R> Seek for ForwardReader<R>
+where
+    R: Read,
+{
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        let target = match pos {
+            SeekFrom::Start(target) => Some(target),
+            SeekFrom::Current(offset) => self.position.checked_add_signed(offset),
+            SeekFrom::End(_) => None,
+        };
+        let Some(target) = target.filter(|target| self.position <= *target) else {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::Unsupported,
+                "読み込み位置を前方にのみ移動できます。",
+            ));
+        };
+        let skip = target - self.position;
+        let skipped = std::io::copy(&mut (&mut *self).take(skip), &mut std::io::sink())?;
+        if skipped < skip {
+            return Err(std::io::Error::from(std::io::ErrorKind::UnexpectedEof));
+        }
+
+        Ok(self.position)
+    }
+}
@@ -0,0 +1,843 @@
+use std::io::Cursor;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use time::macros::datetime;
+use time::{Duration, PrimitiveDateTime};
+
+use super::decode::{expand_run_length, RunLengthKind};
+use super::{
+    decode_run_length, is_missing, output_csv_points_with_options, output_csv_with_geom,
+    read_date_time, DecodeError, ExportOptions, LevelRepetition, Rainfall, RapReader,
+    RapReaderError, RapReaderOptions, RapSource, RapStreamReader, MISSING_VALUE,
+};
+
+/// 最初の観測データの日時
+const FIRST_DATE_TIME: PrimitiveDateTime = datetime!(2020-01-01 01:00);
+
+/// レベル反復表によるランレングス圧縮(a)、レベル反復表によらないランレングス圧縮(b)、
+/// 頻度が多い単独のレベル値(c)及び頻度が少ない単独のレベル値(d)で圧縮した観測値
+///
+/// レベル1を2回(a)、レベル2を1回(c)、レベル3（欠測値）を1回(d)記録している。
+const ALL_KINDS_BLOCK: [u8; 4] = [0x00, 0x82, 0xFE, 0x03];
+
+/// `ALL_KINDS_BLOCK`を展開した観測値
+const ALL_KINDS_VALUES: [Option<u16>; 4] = [Some(10), Some(10), Some(123), None];
+
+/// レベル0を3回(b)、レベル3（欠測値）を1回(c)記録した圧縮データ
+const ZERO_BLOCK: [u8; 3] = [0xC0, 0x01, 0x83];
+
+/// `ZERO_BLOCK`を展開した観測値
+const ZERO_VALUES: [Option<u16>; 4] = [Some(0), Some(0), Some(0), None];
+
+/// テストに使用するRAPファイルのバイト列を生成する定義
+///
+/// 既定値は、北緯36度、東経135度を最北西端の格子の中心とする0.01度間隔の2×2の格子で、
+/// 1時間間隔で観測した24データを記録する。
+/// 観測データは、`ALL_KINDS_BLOCK`と`ZERO_BLOCK`を交互に記録する。
+pub(super) struct RapFixture {
+    /// 作成者コメント（66バイトに満たない場合は空白で埋める）
+    pub(super) creator_comment: Vec<u8>,
+    /// 最北西端の格子の中心の緯度（10e-6度単位）
+    pub(super) start_grid_latitude: u32,
+    /// 最北西端の格子の中心の経度（10e-6度単位）
+    pub(super) start_grid_longitude: u32,
+    /// 格子の幅（10e-6度単位）
+    pub(super) grid_width: u32,
+    /// 格子の高さ（10e-6度単位）
+    pub(super) grid_height: u32,
+    /// 経度方向の格子数
+    pub(super) number_of_h_grids: u16,
+    /// 緯度方向の格子数
+    pub(super) number_of_v_grids: u16,
+    /// レベルごとの観測値
+    pub(super) value_by_levels: Vec<u16>,
+    /// レベル反復数表に記録するレベルと反復数
+    pub(super) level_repetitions: Vec<(u8, u8)>,
+    /// データ部へのインデックスに記録する観測日時と、データ部に記録する圧縮データ
+    pub(super) blocks: Vec<(PrimitiveDateTime, Vec<u8>)>,
+}
+
+impl Default for RapFixture {
+    fn default() -> Self {
+        let blocks = (0..24)
+            .map(|i| {
+                let dt = FIRST_DATE_TIME + Duration::hours(i);
+                let block = if i % 2 == 0 {
+                    ALL_KINDS_BLOCK.to_vec()
+                } else {
+                    ZERO_BLOCK.to_vec()
+                };
+                (dt, block)
+            })
+            .collect();
+
+        Self {
+            creator_comment: b"fixture".to_vec(),
+            start_grid_latitude: 36_000_000,
+            start_grid_longitude: 135_000_000,
+            grid_width: 10_000,
+            grid_height: 10_000,
+            number_of_h_grids: 2,
+            number_of_v_grids: 2,
+            value_by_levels: vec![0, 10, 123, MISSING_VALUE],
+            level_repetitions: vec![(1, 0)],
+            blocks,
+        }
+    }
+}
+
+impl RapFixture {
+    /// RAPファイルのバイト列を生成する。
+    pub(super) fn build(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+
+        // コメント
+        bytes.extend_from_slice(b"JMA-RA01.00");
+        let mut creator_comment = self.creator_comment.clone();
+        creator_comment.resize(66, b' ');
+        bytes.extend_from_slice(&creator_comment);
+        bytes.extend_from_slice(&[0x0d, 0x0a, 0x00]);
+
+        // データ部へのインデックス
+        let header_bytes = 80
+            + 4
+            + self.blocks.len() * 20
+            + 40
+            + 4
+            + self.value_by_levels.len() * 2
+            + 2
+            + self.level_repetitions.len() * 2;
+        bytes.extend_from_slice(&(self.blocks.len() as u32).to_le_bytes());
+        let mut position = header_bytes;
+        for (dt, block) in self.blocks.iter() {
+            bytes.extend_from_slice(&(dt.year() as u16).to_le_bytes());
+            bytes.extend_from_slice(&[dt.month() as u8, dt.day(), dt.hour(), dt.minute()]);
+            bytes.extend_from_slice(&1u16.to_le_bytes());
+            bytes.extend_from_slice(&[0; 8]);
+            bytes.extend_from_slice(&(position as u32).to_le_bytes());
+            position += 4 + block.len() + 12;
+        }
+
+        // 格子系定義
+        bytes.extend_from_slice(&0u16.to_le_bytes());
+        bytes.extend_from_slice(&1u16.to_le_bytes());
+        bytes.extend_from_slice(&self.start_grid_latitude.to_le_bytes());
+        bytes.extend_from_slice(&self.start_grid_longitude.to_le_bytes());
+        bytes.extend_from_slice(&self.grid_width.to_le_bytes());
+        bytes.extend_from_slice(&self.grid_height.to_le_bytes());
+        bytes.extend_from_slice(&self.number_of_h_grids.to_le_bytes());
+        bytes.extend_from_slice(&self.number_of_v_grids.to_le_bytes());
+        bytes.extend_from_slice(&[0; 16]);
+
+        // 圧縮方法、観測値表、レベル反復数表
+        bytes.extend_from_slice(&1u16.to_le_bytes());
+        bytes.extend_from_slice(&(self.value_by_levels.len() as u16).to_le_bytes());
+        for value in self.value_by_levels.iter() {
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+        bytes.extend_from_slice(&(self.level_repetitions.len() as u16).to_le_bytes());
+        for (level, repetition) in self.level_repetitions.iter() {
+            bytes.extend_from_slice(&[*level, *repetition]);
+        }
+        assert_eq!(bytes.len(), header_bytes);
+
+        // データ部
+        for (index, (_, block)) in self.blocks.iter().enumerate() {
+            bytes.extend_from_slice(&(block.len() as u32).to_le_bytes());
+            bytes.extend_from_slice(block);
+            bytes.extend_from_slice(&(0xAB00 + index as u64).to_le_bytes());
+            bytes.extend_from_slice(&(900 + index as u32).to_le_bytes());
+        }
+
+        bytes
+    }
+
+    /// RAPファイルのバイト列を生成して、`RapReader`で読み込む。
+    pub(super) fn reader(&self) -> RapReader {
+        RapReader::from_bytes(self.build()).unwrap()
+    }
+}
+
+/// テストで出力するファイルの、一時ディレクトリ内のパスを返す。
+fn temp_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("jma-{name}-{}.RAP", std::process::id()))
+}
+
+/// 引数で指定されたインデックスの観測値を、記録順に返す。
+fn values_by_index(reader: &RapReader, index: usize) -> Vec<Option<u16>> {
+    reader
+        .value_iterator_by_index(index)
+        .unwrap()
+        .map(|lv| lv.map(|lv| lv.value))
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap()
+}
+
+/// 圧縮データを1バイトずつ返すクロージャーを使用して、観測値を1つ展開する。
+fn expand(
+    compressed: &[u8],
+    value_by_levels: &[u16],
+    level_repetitions: &[LevelRepetition],
+) -> Result<(RunLengthKind, u8, u16, u16), DecodeError> {
+    let mut bytes = compressed.iter().copied();
+    let ev = expand_run_length(
+        0,
+        0,
+        || bytes.next().ok_or(DecodeError::UnexpectedEnd { offset: 0 }),
+        value_by_levels,
+        level_repetitions,
+    )?;
+
+    Ok((ev.kind, ev.level, ev.value, ev.number_of_repetitions))
+}
+
+#[test]
+fn from_bytes_reads_management_part() {
+    let reader = RapFixture::default().reader();
+
+    assert_eq!(reader.identifier(), "JMA-RA");
+    assert_eq!(reader.version(), "01.00");
+    assert_eq!(reader.creator_comment(), "fixture");
+    assert_eq!(reader.number_of_data(), 24);
+    assert_eq!(reader.grid_start_latitude(), 36_000_000);
+    assert_eq!(reader.grid_start_longitude(), 135_000_000);
+    assert_eq!(reader.number_of_h_grids(), 2);
+    assert_eq!(reader.number_of_v_grids(), 2);
+    assert_eq!(reader.value_by_levels(), [0, 10, 123, MISSING_VALUE]);
+    let level_repetitions = reader
+        .level_repetitions()
+        .iter()
+        .map(|lr| (lr.level, lr.actual_repetition()))
+        .collect::<Vec<_>>();
+    assert_eq!(level_repetitions, [(1, 2)]);
+
+    let dp = reader.data_properties()[1];
+    assert_eq!(dp.observation_date_time, datetime!(2020-01-01 02:00));
+    assert_eq!(dp.compressed_data_size, ZERO_BLOCK.len() as u32);
+    assert_eq!(dp.radar_operation_statuses, 0xAB01);
+    assert_eq!(dp.number_of_amedas, 901);
+}
+
+#[test]
+fn from_reader_defers_data_block_metadata_when_lazy() {
+    let bytes: Arc<[u8]> = RapFixture::default().build().into();
+    let mut cursor = Cursor::new(Arc::clone(&bytes));
+    let reader = RapReader::from_reader(
+        RapSource::Bytes { bytes, path: None },
+        &mut cursor,
+        &RapReaderOptions::default(),
+        true,
+    )
+    .unwrap();
+
+    assert_eq!(reader.data_properties()[0].compressed_data_size, 0);
+    let dp = reader.data_property(FIRST_DATE_TIME).unwrap();
+    assert_eq!(dp.compressed_data_size, ALL_KINDS_BLOCK.len() as u32);
+    assert_eq!(dp.number_of_amedas, 900);
+    assert_eq!(reader.to_grid(FIRST_DATE_TIME).unwrap(), ALL_KINDS_VALUES);
+}
+
+#[test]
+fn new_reads_file() {
+    let path = std::env::temp_dir().join(format!("jma-fixture-{}.RAP", std::process::id()));
+    std::fs::write(&path, RapFixture::default().build()).unwrap();
+    let result = RapReader::new(&path).and_then(|reader| {
+        let grids = reader
+            .data_properties()
+            .iter()
+            .map(|dp| reader.to_grid(dp.observation_date_time))
+            .collect::<Result<Vec<_>, _>>();
+        grids.map(|grids| (reader.creator_comment().to_string(), grids))
+    });
+    std::fs::remove_file(&path).unwrap();
+
+    let (creator_comment, grids) = result.unwrap();
+    assert_eq!(creator_comment, "fixture");
+    assert_eq!(grids.len(), 24);
+    assert_eq!(grids[0], ALL_KINDS_VALUES);
+    assert_eq!(grids[1], ZERO_VALUES);
+}
+
+#[test]
+fn value_iterator_walks_west_to_east_then_north_to_south() {
+    let reader = RapFixture::default().reader();
+    let coordinates = reader
+        .value_iterator(FIRST_DATE_TIME)
+        .unwrap()
+        .map(|lv| lv.map(|lv| (lv.latitude, lv.longitude)))
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+
+    assert_eq!(
+        coordinates,
+        [
+            (36.0, 135.0),
+            (36.0, 135.01),
+            (35.99, 135.0),
+            (35.99, 135.01)
+        ]
+    );
+}
+
+#[test]
+fn value_iterator_reports_missing_value_as_none() {
+    let reader = RapFixture::default().reader();
+    let values = |dt| {
+        reader
+            .value_iterator(dt)
+            .unwrap()
+            .map(|lv| lv.map(|lv| lv.value))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap()
+    };
+
+    assert_eq!(values(FIRST_DATE_TIME), ALL_KINDS_VALUES);
+    assert_eq!(values(datetime!(2020-01-01 02:00)), ZERO_VALUES);
+}
+
+#[test]
+fn expand_run_length_decodes_each_kind() {
+    let value_by_levels = [0, 10, 123, MISSING_VALUE];
+    let level_repetitions = [LevelRepetition {
+        level: 1,
+        repetition: 3,
+    }];
+    let expand = |compressed: &[u8]| expand(compressed, &value_by_levels, &level_repetitions);
+
+    // レベル反復表の0番目の、レベル1を3+2回
+    assert_eq!(
+        expand(&[0x00]).unwrap(),
+        (RunLengthKind::LevelRepetition, 1, 10, 5)
+    );
+    // レベル2を4+2回
+    assert_eq!(
+        expand(&[0xC2, 0x04]).unwrap(),
+        (RunLengthKind::Repetition, 2, 123, 6)
+    );
+    // レベル2を1回
+    assert_eq!(
+        expand(&[0x82]).unwrap(),
+        (RunLengthKind::FrequentLevel, 2, 123, 1)
+    );
+    // レベル3を1回
+    assert_eq!(
+        expand(&[0xFE, 0x03]).unwrap(),
+        (RunLengthKind::InfrequentLevel, 3, MISSING_VALUE, 1)
+    );
+}
+
+#[test]
+fn expand_run_length_rejects_unknown_byte() {
+    let value_by_levels = [0, 10, 123, MISSING_VALUE];
+
+    assert!(matches!(
+        expand(&[0xFF], &value_by_levels, &[]),
+        Err(DecodeError::UnknownRunLengthByte { byte: 0xFF, .. })
+    ));
+    assert!(matches!(
+        expand(&[0x00], &value_by_levels, &[]),
+        Err(DecodeError::LevelRepetitionOutOfRange { index: 0, .. })
+    ));
+    assert!(matches!(
+        expand(&[0x84], &value_by_levels, &[]),
+        Err(DecodeError::LevelOutOfRange { level: 4, .. })
+    ));
+}
+
+#[test]
+fn to_grid_reports_unknown_run_length_byte() {
+    let mut fixture = RapFixture::default();
+    fixture.blocks[0].1 = vec![0x81, 0xFF, 0x81, 0x81];
+    let reader = fixture.reader();
+
+    assert!(matches!(
+        reader.to_grid(FIRST_DATE_TIME),
+        Err(RapReaderError::UnknownRunLengthByte {
+            byte: 0xFF,
+            offset: 1,
+            cell_index: 1
+        })
+    ));
+}
+
+#[test]
+fn first_cell_wkt_is_centered_on_grid_origin() {
+    let reader = RapFixture::default().reader();
+    let iterator = reader.value_iterator(FIRST_DATE_TIME).unwrap();
+    let mut csv = Vec::new();
+    output_csv_with_geom(
+        &mut csv,
+        iterator,
+        reader.grid_width_deg(),
+        reader.grid_height_deg(),
+    )
+    .unwrap();
+    let csv = String::from_utf8(csv).unwrap();
+
+    // 格子系定義の最初のデータの緯度と経度は、最北西端の格子の中心を示す
+    assert_eq!(
+        csv.lines().nth(1).unwrap(),
+        "135.000000,36.000000,10,\"POLYGON((134.995000 36.005000,135.005000 36.005000,\
+         135.005000 35.995000,134.995000 35.995000, 134.995000 36.005000))\""
+    );
+}
+
+#[test]
+fn max_level_value_is_missing_everywhere() {
+    let mut fixture = RapFixture::default();
+    // レベル3（欠測値）を、(b)、(c)及び(d)で1格子ずつ記録
+    fixture.blocks[0].1 = vec![0xC3, 0x00, 0x83, 0xFE, 0x03];
+    let reader = fixture.reader();
+    let compressed = reader.compressed_block(FIRST_DATE_TIME).unwrap();
+
+    assert!(is_missing(MISSING_VALUE));
+    assert!(!is_missing(MISSING_VALUE - 1));
+    assert_eq!(reader.to_grid(FIRST_DATE_TIME).unwrap(), [None; 4]);
+    assert!(reader
+        .value_iterator(FIRST_DATE_TIME)
+        .unwrap()
+        .all(|lv| lv.unwrap().value.is_none()));
+    assert_eq!(
+        decode_run_length(
+            &compressed,
+            reader.value_by_levels(),
+            reader.level_repetitions()
+        )
+        .unwrap(),
+        [None; 4]
+    );
+
+    let mut printed = Vec::new();
+    reader.pretty_print(&mut printed).unwrap();
+    let printed = String::from_utf8(printed).unwrap();
+    assert!(printed.contains(&format!("{:>9}{:>12}", 3, "None")));
+    assert!(!printed.contains(&MISSING_VALUE.to_string()));
+}
+
+#[test]
+fn max_cell_prefers_first_of_ties_and_ignores_missing() {
+    let mut fixture = RapFixture::default();
+    // 10、123、123、欠測値
+    fixture.blocks[0].1 = vec![0x81, 0xC2, 0x00, 0x83];
+    // すべて欠測値
+    fixture.blocks[1].1 = vec![0xC3, 0x02];
+    let reader = fixture.reader();
+
+    let max = reader.max_cell(FIRST_DATE_TIME).unwrap().unwrap();
+    assert_eq!(max.value, Some(123));
+    assert_eq!((max.latitude, max.longitude), (36.0, 135.01));
+    assert!(reader
+        .max_cell(datetime!(2020-01-01 02:00))
+        .unwrap()
+        .is_none());
+}
+
+#[test]
+fn creator_comment_excludes_nul_padding() {
+    let mut creator_comment = b"nul padded".to_vec();
+    creator_comment.resize(66, 0x00);
+    let fixture = RapFixture {
+        creator_comment,
+        ..Default::default()
+    };
+    let reader = fixture.reader();
+
+    assert_eq!(reader.creator_comment(), "nul padded");
+    let mut printed = Vec::new();
+    reader.pretty_print(&mut printed).unwrap();
+    assert!(!printed.contains(&0x00));
+}
+
+#[test]
+fn by_index_lookups_are_bounds_checked() {
+    let reader = RapFixture::default().reader();
+
+    let dp = reader.data_property_by_index(1).unwrap();
+    assert_eq!(dp.observation_date_time, datetime!(2020-01-01 02:00));
+    let values = reader
+        .value_iterator_by_index(1)
+        .unwrap()
+        .map(|lv| lv.map(|lv| lv.value))
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(values, ZERO_VALUES);
+
+    assert!(reader.data_property_by_index(24).is_none());
+    assert!(matches!(
+        reader.value_iterator_by_index(24),
+        Err(RapReaderError::DataIndexOutOfRange {
+            index: 24,
+            number_of_data: 24
+        })
+    ));
+}
+
+#[test]
+fn csv_distinguishes_zero_from_missing() {
+    let reader = RapFixture::default().reader();
+    let output = |options: &ExportOptions| {
+        let iterator = reader.value_iterator(datetime!(2020-01-01 02:00)).unwrap();
+        let mut csv = Vec::new();
+        output_csv_points_with_options(&mut csv, iterator, options, |_, _| {}).unwrap();
+        String::from_utf8(csv).unwrap()
+    };
+
+    let csv = output(&ExportOptions::default());
+    let lines = csv.lines().collect::<Vec<_>>();
+    assert_eq!(lines[1], "135.000000,36.000000,0");
+    assert_eq!(lines[4], "135.010000,35.990000,");
+
+    let options = ExportOptions {
+        missing: String::from("NA").into(),
+        ..Default::default()
+    };
+    let csv = output(&options);
+    let lines = csv.lines().collect::<Vec<_>>();
+    assert_eq!(lines[1], "135.000000,36.000000,0");
+    assert_eq!(lines[4], "135.010000,35.990000,NA");
+}
+
+#[test]
+fn zero_size_block_is_empty_block_error() {
+    let mut fixture = RapFixture::default();
+    fixture.blocks[0].1.clear();
+    let bytes = fixture.build();
+    let reader = RapReader::from_bytes(bytes.clone()).unwrap();
+
+    assert_eq!(reader.data_properties()[0].compressed_data_size, 0);
+    assert!(matches!(
+        reader.value_iterator(FIRST_DATE_TIME),
+        Err(RapReaderError::EmptyBlock(dt)) if dt == FIRST_DATE_TIME
+    ));
+    assert!(matches!(
+        reader.to_grid(FIRST_DATE_TIME),
+        Err(RapReaderError::EmptyBlock(dt)) if dt == FIRST_DATE_TIME
+    ));
+    let mut stream = RapStreamReader::new(Cursor::new(bytes)).unwrap();
+    assert!(matches!(
+        stream.next(),
+        Some(Err(RapReaderError::EmptyBlock(dt))) if dt == FIRST_DATE_TIME
+    ));
+    // 他の日時の観測値は展開できる
+    assert_eq!(
+        reader.to_grid(datetime!(2020-01-01 02:00)).unwrap(),
+        ZERO_VALUES
+    );
+    assert_eq!(stream.next().unwrap().unwrap().1, ZERO_VALUES);
+}
+
+#[test]
+fn value_iterator_stops_at_latitude_underflow() {
+    // 北緯0.01度から1行の格子系定義に、3行分の観測値を記録
+    let mut fixture = RapFixture {
+        start_grid_latitude: 10_000,
+        number_of_v_grids: 1,
+        ..Default::default()
+    };
+    fixture.blocks[0].1 = vec![0xC1, 0x04];
+    let reader = fixture.reader();
+    let mut iterator = reader.value_iterator(FIRST_DATE_TIME).unwrap();
+
+    let latitudes = iterator
+        .by_ref()
+        .take(4)
+        .map(|lv| lv.map(|lv| lv.latitude))
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(latitudes, [0.01, 0.01, 0.0, 0.0]);
+    assert!(matches!(
+        iterator.next(),
+        Some(Err(RapReaderError::LatitudeUnderflow { cell_index: 4 }))
+    ));
+    assert!(iterator.next().is_none());
+}
+
+#[test]
+fn hour_24_rolls_over_to_next_day() {
+    let date_time = |bytes: [u8; 6]| read_date_time(&mut Cursor::new(bytes));
+    let [y0, y1] = 2020u16.to_le_bytes();
+
+    assert_eq!(
+        date_time([y0, y1, 12, 31, 24, 0]).unwrap(),
+        datetime!(2021-01-01 00:00)
+    );
+    assert!(matches!(
+        date_time([y0, y1, 12, 31, 24, 30]),
+        Err(RapReaderError::InvalidTime {
+            hour: 24,
+            minute: 30
+        })
+    ));
+
+    // 最後のデータ部へのインデックスの観測日時を、2020年1月1日24時00分に書き換える
+    let mut bytes = RapFixture::default().build();
+    let offset = 84 + 23 * 20;
+    assert_eq!(bytes[offset + 3..offset + 5], [2, 0]);
+    bytes[offset + 3..offset + 5].copy_from_slice(&[1, 24]);
+    let reader = RapReader::from_bytes(bytes).unwrap();
+    assert_eq!(
+        reader.data_properties()[23].observation_date_time,
+        datetime!(2020-01-02 00:00)
+    );
+}
+
+#[test]
+fn decode_run_length_returns_values_in_record_order() {
+    let value_by_levels = [0, 10, 123, MISSING_VALUE];
+    let level_repetitions = [LevelRepetition {
+        level: 1,
+        repetition: 0,
+    }];
+    let decode =
+        |compressed: &[u8]| decode_run_length(compressed, &value_by_levels, &level_repetitions);
+
+    // 格子数と照合しないため、格子数の倍数でない数の観測値を返す
+    assert_eq!(
+        decode(&[0x00, 0xC0, 0x01, 0x82, 0xFE, 0x03]).unwrap(),
+        [
+            Some(10),
+            Some(10),
+            Some(0),
+            Some(0),
+            Some(0),
+            Some(123),
+            None
+        ]
+    );
+    assert_eq!(decode(&[]).unwrap(), []);
+    assert!(matches!(
+        decode(&[0x82, 0xFF]),
+        Err(RapReaderError::UnknownRunLengthByte {
+            byte: 0xFF,
+            offset: 1,
+            cell_index: 1
+        })
+    ));
+    assert!(matches!(
+        decode(&[0x82, 0xC0]),
+        Err(RapReaderError::Decode(DecodeError::UnexpectedEnd { .. }))
+    ));
+}
+
+#[test]
+fn duplicated_timestamps_are_resolved_by_index() {
+    // 2番目の観測データに、最初の観測データと同じ日時を記録
+    let mut fixture = RapFixture::default();
+    fixture.blocks[1].0 = FIRST_DATE_TIME;
+    let reader = fixture.reader();
+
+    assert_eq!(values_by_index(&reader, 0), ALL_KINDS_VALUES);
+    assert_eq!(values_by_index(&reader, 1), ZERO_VALUES);
+    let grids = reader.decode_all().unwrap();
+    assert_eq!(grids[0].1.values(), ALL_KINDS_VALUES);
+    assert_eq!(grids[1].1.values(), ZERO_VALUES);
+    reader.verify().unwrap();
+    let layout = reader.block_layout();
+    assert_eq!(layout[1].compressed_data_size, ZERO_BLOCK.len() as u32);
+    assert_eq!(layout[1].number_of_amedas, 901);
+
+    // 最初の観測データのみを変換して、2番目の観測データはそのまま記録する
+    let path = temp_path("duplicated");
+    reader
+        .rewrite_with(FIRST_DATE_TIME, &path, |_, _, _| Some(10))
+        .unwrap();
+    let rewritten = RapReader::from_bytes(std::fs::read(&path).unwrap()).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(values_by_index(&rewritten, 0), [Some(10); 4]);
+    assert_eq!(values_by_index(&rewritten, 1), ZERO_VALUES);
+}
+
+#[cfg(feature = "geo")]
+#[test]
+fn geometry_ignores_coordinate_anchor() {
+    let reader = RapFixture::default().reader();
+    let polygons = |anchor| {
+        reader
+            .value_iterator(FIRST_DATE_TIME)
+            .unwrap()
+            .with_anchor(anchor)
+            .with_geometry()
+            .map(|item| item.map(|(polygon, _)| polygon))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap()
+    };
+
+    let centered = polygons(super::CoordinateAnchor::Center);
+    let corner = polygons(super::CoordinateAnchor::NorthWestCorner);
+    assert_eq!(centered, corner);
+    let exterior = corner[0]
+        .exterior()
+        .points()
+        .map(|p| (p.x(), p.y()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        exterior,
+        [
+            (134.995, 36.005),
+            (135.005, 36.005),
+            (135.005, 35.995),
+            (134.995, 35.995),
+            (134.995, 36.005)
+        ]
+    );
+}
+
+/// 引数の定義で生成したRAPファイルの観測値を変換して、出力したRAPファイルを読み込む。
+fn rewrite<F>(fixture: &RapFixture, name: &str, f: F) -> RapReader
+where
+    F: FnMut(u16, u16, Option<u16>) -> Option<u16>,
+{
+    let path = temp_path(name);
+    let result = fixture
+        .reader()
+        .rewrite_with(FIRST_DATE_TIME, &path, f)
+        .and_then(|_| std::fs::read(&path).map_err(RapReaderError::from));
+    std::fs::remove_file(&path).ok();
+
+    RapReader::from_bytes(result.unwrap()).unwrap()
+}
+
+#[test]
+fn rewrite_with_round_trips_masked_values() {
+    let fixture = RapFixture::default();
+    // 南の行を欠測値にして、北東の格子に観測値表に存在しない観測値を記録
+    let rewritten = rewrite(&fixture, "masked", |row, col, value| match (row, col) {
+        (1, _) => None,
+        (0, 1) => Some(500),
+        _ => value,
+    });
+
+    assert_eq!(
+        rewritten.value_by_levels(),
+        [0, 10, 123, MISSING_VALUE, 500]
+    );
+    assert_eq!(
+        rewritten.to_grid(FIRST_DATE_TIME).unwrap(),
+        [Some(10), Some(500), None, None]
+    );
+    let original = fixture.reader();
+    for dp in original.data_properties().iter().skip(1) {
+        let dt = dp.observation_date_time;
+        assert_eq!(
+            rewritten.to_grid(dt).unwrap(),
+            original.to_grid(dt).unwrap()
+        );
+        assert_eq!(rewritten.amedas_count(dt), original.amedas_count(dt));
+    }
+    rewritten.verify().unwrap();
+}
+
+#[test]
+fn rewrite_with_encodes_infrequent_levels() {
+    // レベル64以降は、頻度が少ない単独のレベル値(d)で記録する
+    let fixture = RapFixture {
+        value_by_levels: (0..70).chain([MISSING_VALUE]).collect(),
+        ..Default::default()
+    };
+    let rewritten = rewrite(&fixture, "infrequent", |row, col, _| match (row, col) {
+        (0, _) => Some(69),
+        (1, 0) => Some(1_000),
+        _ => None,
+    });
+
+    assert_eq!(rewritten.value_by_levels().len(), 72);
+    assert_eq!(
+        rewritten.compressed_block(FIRST_DATE_TIME).unwrap(),
+        [0xFE, 69, 0xFE, 69, 0xFE, 71, 0xFE, 70]
+    );
+    assert_eq!(
+        rewritten.to_grid(FIRST_DATE_TIME).unwrap(),
+        [Some(69), Some(69), Some(1_000), None]
+    );
+}
+
+#[test]
+fn rewrite_with_rejects_source_file() {
+    let path = temp_path("same-file");
+    let bytes = RapFixture::default().build();
+    std::fs::write(&path, &bytes).unwrap();
+    let result = RapReader::new(&path)
+        .and_then(|reader| reader.rewrite_with(FIRST_DATE_TIME, &path, |_, _, value| value));
+    let written = std::fs::read(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert!(matches!(result, Err(RapReaderError::Write(_))));
+    assert_eq!(written, bytes);
+}
+
+#[test]
+fn rolling_accumulation_accepts_huge_window() {
+    let reader = RapFixture::default().reader();
+    let sums = reader
+        .rolling_accumulation(usize::MAX, true)
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+
+    assert_eq!(sums.len(), 24);
+    assert_eq!(sums[1].1, [Some(10), Some(10), Some(123), None]);
+}
+
+#[test]
+fn rainfall_keeps_missing_separate() {
+    let reader = RapFixture::default().reader();
+    let rainfalls = reader
+        .value_iterator(FIRST_DATE_TIME)
+        .unwrap()
+        .map(|lv| lv.map(|lv| lv.rainfall()))
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+
+    assert_eq!(
+        rainfalls,
+        [
+            Rainfall::new(10),
+            Rainfall::new(10),
+            Rainfall::new(123),
+            None
+        ]
+    );
+    assert_eq!(Rainfall::new(MISSING_VALUE), None);
+    assert_eq!(reader.rainfall_for_level(3), None);
+    assert_eq!(reader.rainfall_for_level(2).unwrap().to_string(), "12.3 mm");
+}
+
+#[cfg(feature = "gzip")]
+#[test]
+fn expected_date_checks_gzip_file_name() {
+    use std::io::Write as _;
+
+    let dir = std::env::temp_dir().join(format!("jma-gzip-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let mut encoder = ::flate2::write::GzEncoder::new(Vec::new(), ::flate2::Compression::default());
+    encoder.write_all(&RapFixture::default().build()).unwrap();
+    let compressed = encoder.finish().unwrap();
+    let matched = dir.join("J2020101.RAP.gz");
+    let mismatched = dir.join("J2020102.RAP.gz");
+    std::fs::write(&matched, &compressed).unwrap();
+    std::fs::write(&mismatched, &compressed).unwrap();
+
+    let expected = RapReader::new(&matched).unwrap().expected_date();
+    let error = RapReader::open_lazy(&mismatched)
+        .unwrap()
+        .expected_date()
+        .unwrap_err();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(expected.unwrap(), Some(FIRST_DATE_TIME.date()));
+    assert!(matches!(error, RapReaderError::FileNameDateMismatch { .. }));
+}
+
+#[test]
+fn truncated_field_reports_its_start_offset() {
+    let bytes = RapFixture::default().build();
+    let error = RapReader::from_bytes(&bytes[..82]).unwrap_err();
+
+    match error {
+        RapReaderError::Parse { offset, field, .. } => {
+            assert_eq!(offset, 80);
+            assert_eq!(field, "データ部へのインデックスのデータ数");
+        }
+        e => panic!("unexpected error: {e}"),
+    }
+}
@@ -0,0 +1,88 @@
+use time::PrimitiveDateTime;
+
+use super::{RapReader, RapReaderError, RapReaderResult};
+
+/// タイルの位置と大きさ
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TileCoord {
+    /// 北から数えたタイルの行のインデックス
+    pub row: u16,
+    /// 西から数えたタイルの列のインデックス
+    pub col: u16,
+    /// タイルの経度方向の格子数
+    pub width: u16,
+    /// タイルの緯度方向の格子数
+    pub height: u16,
+}
+
+impl RapReader {
+    /// 引数で指定された日時の観測値を、指定された格子数のタイルに分割して返す。
+    ///
+    /// 観測値を1度だけ展開して、最北西端のタイルから経度方向、緯度方向の優先順位でタイルを返す。
+    /// タイルの観測値は、タイルの最北西端の格子から経度方向、緯度方向の優先順位で格納する。
+    /// 格子数がタイルの格子数で割り切れない場合、東端と南端のタイルは、`pad`が`true`の場合は
+    /// 欠測値の格子で補い、`false`の場合は観測範囲に含まれる格子のみを格納する。
+    ///
+    /// # 引数
+    ///
+    /// * `dt` - 観測値を取得する日時
+    /// * `tile_w` - タイルの経度方向の格子数
+    /// * `tile_h` - タイルの緯度方向の格子数
+    /// * `pad` - 東端と南端のタイルを欠測値の格子で補う場合は`true`
+    ///
+    /// # 戻り値
+    ///
+    /// タイルの位置と大きさ、及びタイルの観測値（欠測値は`None`）を返すイテレーター
+    pub fn tiles(
+        &self,
+        dt: PrimitiveDateTime,
+        tile_w: u16,
+        tile_h: u16,
+        pad: bool,
+    ) -> RapReaderResult<impl Iterator<Item = (TileCoord, Vec<Option<u16>>)>> {
+        if tile_w == 0 || tile_h == 0 {
+            return Err(RapReaderError::InvalidTileSize {
+                width: tile_w,
+                height: tile_h,
+            });
+        }
+        let grid = self.decode(dt)?;
+        let number_of_cols = grid.width().div_ceil(tile_w);
+        let number_of_rows = grid.height().div_ceil(tile_h);
+        let tile_coords =
+            (0..number_of_rows).flat_map(move |row| (0..number_of_cols).map(move |col| (row, col)));
+
+        Ok(tile_coords.map(move |(row, col)| {
+            let top = row as u32 * tile_h as u32;
+            let left = col as u32 * tile_w as u32;
+            let (width, height) = if pad {
+                (tile_w, tile_h)
+            } else {
+                (
+                    (grid.width() as u32 - left).min(tile_w as u32) as u16,
+                    (grid.height() as u32 - top).min(tile_h as u32) as u16,
+                )
+            };
+            let mut values = Vec::with_capacity(width as usize * height as usize);
+            for r in top..top + height as u32 {
+                for c in left..left + width as u32 {
+                    // 観測範囲外の格子は、`get`が`None`を返すため欠測値で補われる
+                    values.push(
+                        u16::try_from(r)
+                            .ok()
+                            .zip(u16::try_from(c).ok())
+                            .and_then(|(r, c)| grid.get(r, c)),
+                    );
+                }
+            }
+            let coord = TileCoord {
+                row,
+                col,
+                width,
+                height,
+            };
+
+            (coord, values)
+        }))
+    }
+}
@@ -0,0 +1,219 @@
+use std::io::{Cursor, SeekFrom};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use ::tokio::fs::File;
+use ::tokio::io::{AsyncReadExt, AsyncSeekExt};
+use time::PrimitiveDateTime;
+
+use super::{
+    ObservationTimes, RapDataReader, RapHeader, RapReader, RapReaderError, RapReaderOptions,
+    RapReaderResult, RapSource, RapValueIterator,
+};
+
+/// 管理部 - コメントのバイト数
+const COMMENT_PART_BYTES: usize = 80;
+
+/// 管理部 - データ部へのインデックスの1データあたりのバイト数
+const DATA_INDEX_ENTRY_BYTES: usize = 20;
+
+/// 管理部 - 格子系定義のバイト数
+const GRID_DEFINITION_PART_BYTES: usize = 40;
+
+impl RapReader {
+    /// RAPファイルを非同期に開く。
+    ///
+    /// 管理部を非同期に読み込んだ後、メモリ上で管理部を解析する。
+    ///
+    /// # 引数
+    ///
+    /// * `path` - 開くRAPファイルのパス
+    ///
+    /// # 戻り値
+    ///
+    /// `RapReader`
+    pub async fn open_async<P>(path: P) -> RapReaderResult<Self>
+    where
+        P: AsRef<Path>,
+    {
+        Self::open_async_with_options(path, RapReaderOptions::default()).await
+    }
+
+    /// オプションを指定してRAPファイルを非同期に開く。
+    ///
+    /// # 引数
+    ///
+    /// * `path` - 開くRAPファイルのパス
+    /// * `options` - RAPファイルを読み込むときのオプション
+    ///
+    /// # 戻り値
+    ///
+    /// `RapReader`
+    pub async fn open_async_with_options<P>(
+        path: P,
+        options: RapReaderOptions,
+    ) -> RapReaderResult<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref().to_path_buf();
+        let mut file = File::open(&path)
+            .await
+            .map_err(|e| RapReaderError::Open(format!("{e}")))?;
+        let mut reader = Cursor::new(read_management_part(&mut file).await?);
+        let RapHeader {
+            comment_part,
+            mut data_index_part,
+            grid_definition,
+            compression_part,
+            level_repetitions_part,
+            reserved_regions,
+        } = RapHeader::parse_with_options(&mut reader, &options)?;
+
+        // データ部に移動してデータ部に記録されている情報を取得
+        for data_property in data_index_part.data_properties.iter_mut() {
+            file.seek(SeekFrom::Start(data_property.data_start_position as u64))
+                .await?;
+            let buf = read_bytes(&mut file, 4, "データ部の圧縮後の大きさ").await?;
+            data_property.compressed_data_size = read_le_u32(&buf);
+            file.seek(SeekFrom::Current(data_property.compressed_data_size as i64))
+                .await?;
+            let buf = read_bytes(
+                &mut file,
+                12,
+                "データ部のレーダー運用状況と解析に使用したアメダスの総数",
+            )
+            .await?;
+            data_property.radar_operation_statuses = read_le_u64(&buf);
+            data_property.number_of_amedas = read_le_u32(&buf[8..]);
+        }
+
+        Ok(Self {
+            source: RapSource::File {
+                path: Arc::from(path),
+                buffer_capacity: options.buffer_capacity,
+            },
+            comment_part,
+            data_index_part,
+            grid_definition,
+            compression_part,
+            level_repetitions_part,
+            shared_reader: Mutex::new(None),
+            loaded_data_properties: None,
+            reserved_regions,
+        })
+    }
+
+    /// 引数で指定された日時の観測値を走査するイテレーターを非同期に構築する。
+    ///
+    /// 圧縮データを非同期にメモリへ読み込むため、イテレーターが観測値を展開するときに
+    /// ファイルを読み込むことはない。
+    /// メモリに読み込む圧縮データの大きさは、`DataProperty::compressed_data_size`である。
+    ///
+    /// # 引数
+    ///
+    /// * `dt` - 観測値を取得する日時
+    ///
+    /// # 戻り値
+    ///
+    /// 観測値を記録順に走査して返すイテレーター
+    pub async fn value_iterator_async(
+        &self,
+        dt: PrimitiveDateTime,
+    ) -> RapReaderResult<RapValueIterator<'_>> {
+        let path = match &self.source {
+            RapSource::File { path, .. } => path,
+            RapSource::Bytes { .. } => return self.value_iterator(dt),
+        };
+        self.ensure_value_iterator_supported()?;
+        let dp = self.resolve_data_property(dt)?;
+
+        let mut file = File::open(path)
+            .await
+            .map_err(|e| RapReaderError::Open(format!("{e}")))?;
+        let file_size = file.metadata().await?.len();
+        let end_position = dp.compressed_data_end_position();
+        if file_size < end_position {
+            return Err(RapReaderError::InvalidBlock {
+                datetime: dp.observation_date_time,
+                reason: format!(
+                    "圧縮データの末尾(0x{end_position:X})がファイルの大きさ(0x{file_size:X})を超えています。"
+                ),
+            });
+        }
+        file.seek(SeekFrom::Start(dp.compressed_data_start_position()))
+            .await?;
+        let compressed =
+            read_bytes(&mut file, dp.compressed_data_size as usize, "圧縮データ").await?;
+        let reader = RapDataReader::Bytes(Cursor::new(Arc::from(compressed)));
+
+        Ok(RapValueIterator::from_grid_definition(
+            reader,
+            dp.compressed_data_size as usize,
+            &self.grid_definition,
+            self.value_by_levels(),
+            self.level_repetitions(),
+        ))
+    }
+}
+
+/// 管理部のバイト列を非同期に読み込む。
+async fn read_management_part(file: &mut File) -> RapReaderResult<Vec<u8>> {
+    // コメントとデータ数
+    let mut buf = read_bytes(file, COMMENT_PART_BYTES + 4, "コメントとデータ数").await?;
+    let number_of_data = read_le_u32(&buf[COMMENT_PART_BYTES..]);
+    let number_of_data = ObservationTimes::try_from(number_of_data)? as usize;
+
+    // データ部へのインデックス、格子系定義、圧縮方法及びレベル数
+    let bytes = number_of_data * DATA_INDEX_ENTRY_BYTES + GRID_DEFINITION_PART_BYTES + 4;
+    buf.extend(read_bytes(file, bytes, "データ部へのインデックスと格子系定義").await?);
+    let number_of_levels = read_le_u16(&buf[buf.len() - 2..]) as usize;
+
+    // レベルごとの観測値及びレベル反復表の大きさ
+    let bytes = number_of_levels * 2 + 2;
+    buf.extend(read_bytes(file, bytes, "圧縮方法・観測値表").await?);
+    let number_of_level_repetitions = read_le_u16(&buf[buf.len() - 2..]) as usize;
+
+    // レベル反復表
+    let bytes = number_of_level_repetitions * 2;
+    buf.extend(read_bytes(file, bytes, "レベル・反復表").await?);
+
+    Ok(buf)
+}
+
+/// ファイルから引数で指定されたバイト数を非同期に読み込む。
+///
+/// 事前にバイト数分のメモリを確保せず、読み込んだバイト列の分だけメモリを確保する。
+async fn read_bytes(
+    file: &mut File,
+    bytes: usize,
+    field: &'static str,
+) -> RapReaderResult<Vec<u8>> {
+    let offset = file.stream_position().await?;
+    let mut buf = Vec::new();
+    file.take(bytes as u64)
+        .read_to_end(&mut buf)
+        .await
+        .map_err(|e| RapReaderError::parse(offset, field, e))?;
+    if buf.len() < bytes {
+        return Err(RapReaderError::parse(
+            offset,
+            field,
+            std::io::Error::from(std::io::ErrorKind::UnexpectedEof),
+        ));
+    }
+
+    Ok(buf)
+}
+
+fn read_le_u16(bytes: &[u8]) -> u16 {
+    u16::from_le_bytes(bytes[..2].try_into().unwrap())
+}
+
+fn read_le_u32(bytes: &[u8]) -> u32 {
+    u32::from_le_bytes(bytes[..4].try_into().unwrap())
+}
+
+fn read_le_u64(bytes: &[u8]) -> u64 {
+    u64::from_le_bytes(bytes[..8].try_into().unwrap())
+}
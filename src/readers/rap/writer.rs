@@ -0,0 +1,231 @@
+use std::fs::OpenOptions;
+use std::io::{BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::Path;
+
+use time::PrimitiveDateTime;
+
+use super::{
+    read_bytes, RapReader, RapReaderError, RapReaderResult, RapSource, COMPRESSED_DATA_SIZE_BYTES,
+    MISSING_VALUE, REPETITION_OFFSET,
+};
+
+/// 管理部 - コメント及びデータ数のバイト数
+const COMMENT_AND_NUMBER_OF_DATA_BYTES: usize = 84;
+
+/// 管理部 - データ部へのインデックスの1データあたりのバイト数
+const DATA_INDEX_ENTRY_BYTES: usize = 20;
+
+/// 管理部 - データ部へのインデックスの1データ内の、データの開始位置までのバイト数
+const DATA_START_POSITION_OFFSET: usize = 16;
+
+/// 管理部 - 格子系定義のバイト数
+const GRID_DEFINITION_PART_BYTES: usize = 40;
+
+/// ランレングス圧縮(d)で記録できるレベルの最大値
+const MAX_LEVEL: usize = u8::MAX as usize;
+
+impl RapReader {
+    /// 引数で指定された日時の観測値を格子ごとに変換して、新しいRAPファイルに出力する。
+    ///
+    /// 引数で指定された日時の観測値は、変換した後にランレングス圧縮して記録する。
+    /// コメント、格子系定義、レベル反復数表及びそれ以外の日時の圧縮データは、変換せずにそのまま記録する。
+    /// 変換した観測値が観測値表に存在しない場合は、観測値表の末尾にレベルを追加する。
+    /// データ部は、データ部へのインデックスの順に隙間なく記録する。
+    /// 同じ日時の観測データが複数記録されている場合は、最初の観測データのみを変換する。
+    /// 変換しない圧縮データを読み込み元から複写しながら出力するため、読み込み元と同じファイルには出力できない。
+    ///
+    /// # 引数
+    ///
+    /// * `dt` - 変換する観測値の日時
+    /// * `out` - 出力するRAPファイルのパス
+    /// * `f` - 北からの行のインデックス、西からの列のインデックス及び観測値を受け取り、
+    ///   変換後の観測値を返すクロージャー（欠測値は`None`）
+    pub fn rewrite_with<F>(
+        &self,
+        dt: PrimitiveDateTime,
+        out: &Path,
+        mut f: F,
+    ) -> RapReaderResult<()>
+    where
+        F: FnMut(u16, u16, Option<u16>) -> Option<u16>,
+    {
+        if let RapSource::File { path, .. } = &self.source {
+            if is_same_file(path, out) {
+                return Err(RapReaderError::Write(format!(
+                    "出力するRAPファイル({})が、読み込み元のRAPファイルと同じです。",
+                    out.display()
+                )));
+            }
+        }
+        let number_of_h_grids = self.number_of_h_grids() as usize;
+        let mut value_by_levels = self.value_by_levels().to_vec();
+        let levels = self
+            .to_grid(dt)?
+            .into_iter()
+            .enumerate()
+            .map(|(index, value)| {
+                let row = (index / number_of_h_grids) as u16;
+                let col = (index % number_of_h_grids) as u16;
+                let value = f(row, col, value).unwrap_or(MISSING_VALUE);
+                level_of(&mut value_by_levels, value)
+            })
+            .collect::<RapReaderResult<Vec<_>>>()?;
+        let compressed = encode_run_length(&levels);
+        // 同じ日時の観測データが複数記録されている場合は、`to_grid`と同様に最初の観測データを変換する
+        let target = self
+            .data_properties()
+            .iter()
+            .position(|dp| dp.observation_date_time == dt)
+            .ok_or(RapReaderError::DataDoesNotRecorded(dt))?;
+
+        // コメント、データ数、データ部へのインデックス及び格子系定義
+        let number_of_data = self.data_properties().len();
+        let head_bytes = COMMENT_AND_NUMBER_OF_DATA_BYTES
+            + number_of_data * DATA_INDEX_ENTRY_BYTES
+            + GRID_DEFINITION_PART_BYTES;
+        let mut reader = self.source.open()?;
+        reader.seek(SeekFrom::Start(0))?;
+        let mut header = read_bytes(&mut reader, head_bytes, "管理部")?;
+
+        // 圧縮方法、観測値表
+        header.extend(self.compression_method().to_le_bytes());
+        header.extend((value_by_levels.len() as u16).to_le_bytes());
+        for value in value_by_levels.iter() {
+            header.extend(value.to_le_bytes());
+        }
+
+        // レベル反復数表
+        header.extend(self.number_of_level_repetitions().to_le_bytes());
+        for lr in self.level_repetitions() {
+            header.extend([lr.level, lr.repetition]);
+        }
+
+        // データ部へのインデックスのデータの開始位置を更新
+        // データ部は圧縮後の大きさ、圧縮データ、レーダー運用状況(8バイト)及びアメダスの総数(4バイト)
+        let mut data_properties = Vec::with_capacity(number_of_data);
+        let mut position = header.len() as u64;
+        for index in 0..number_of_data {
+            let dp = *self.resolve_data_property_at(index)?;
+            let offset = COMMENT_AND_NUMBER_OF_DATA_BYTES
+                + index * DATA_INDEX_ENTRY_BYTES
+                + DATA_START_POSITION_OFFSET;
+            let start_position = u32::try_from(position).map_err(|_| {
+                RapReaderError::Write(format!(
+                    "データ部の開始位置({position})がファイルに記録できる範囲を超えています。"
+                ))
+            })?;
+            header[offset..offset + 4].copy_from_slice(&start_position.to_le_bytes());
+            let compressed_data_size = if index == target {
+                compressed.len() as u64
+            } else {
+                dp.compressed_data_size as u64
+            };
+            position += COMPRESSED_DATA_SIZE_BYTES + compressed_data_size + 8 + 4;
+            data_properties.push(dp);
+        }
+
+        let file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(out)?;
+        let mut writer = BufWriter::new(file);
+        writer.write_all(&header)?;
+
+        // データ部
+        // 変換しない日時の圧縮データは、メモリに保持せずに読み込み元から出力先に複写する。
+        for (index, dp) in data_properties.into_iter().enumerate() {
+            if index == target {
+                writer.write_all(&(compressed.len() as u32).to_le_bytes())?;
+                writer.write_all(&compressed)?;
+            } else {
+                writer.write_all(&dp.compressed_data_size.to_le_bytes())?;
+                reader.seek(SeekFrom::Start(dp.compressed_data_start_position()))?;
+                let copied = std::io::copy(
+                    &mut (&mut reader).take(dp.compressed_data_size as u64),
+                    &mut writer,
+                )?;
+                if copied < dp.compressed_data_size as u64 {
+                    return Err(RapReaderError::InvalidBlock {
+                        datetime: dp.observation_date_time,
+                        reason: format!(
+                            "圧縮データの大きさ({})より前に、ファイルの末尾に達しました。",
+                            dp.compressed_data_size
+                        ),
+                    });
+                }
+            }
+            writer.write_all(&dp.radar_operation_statuses.to_le_bytes())?;
+            writer.write_all(&dp.number_of_amedas.to_le_bytes())?;
+        }
+        writer.flush()?;
+
+        Ok(())
+    }
+}
+
+/// 2つのパスが同じファイルを示す場合は`true`を返す。
+///
+/// いずれかのファイルが存在しない場合は`false`を返す。
+fn is_same_file(a: &Path, b: &Path) -> bool {
+    match (std::fs::canonicalize(a), std::fs::canonicalize(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false,
+    }
+}
+
+/// 観測値のレベルを返す。
+///
+/// 観測値が観測値表に存在しない場合は、観測値表の末尾にレベルを追加する。
+fn level_of(value_by_levels: &mut Vec<u16>, value: u16) -> RapReaderResult<u8> {
+    let level = match value_by_levels.iter().position(|v| *v == value) {
+        Some(level) => level,
+        None => {
+            value_by_levels.push(value);
+            value_by_levels.len() - 1
+        }
+    };
+    if MAX_LEVEL < level {
+        return Err(RapReaderError::Write(format!(
+            "観測値表に記録できるレベルの数({})を超えました。",
+            MAX_LEVEL + 1
+        )));
+    }
+
+    Ok(level as u8)
+}
+
+/// 格子ごとのレベルをランレングス圧縮する。
+///
+/// レベル反復表によらないランレングス圧縮(b)、頻度が多い単独のレベル値(c)及び
+/// 頻度が少ない単独のレベル値(d)で圧縮する。
+fn encode_run_length(levels: &[u8]) -> Vec<u8> {
+    let max_repetitions = u8::MAX as usize + REPETITION_OFFSET as usize;
+    let mut compressed = Vec::new();
+    let mut index = 0;
+    while index < levels.len() {
+        let level = levels[index];
+        let mut count = levels[index..].iter().take_while(|l| **l == level).count();
+        index += count;
+        while 0 < count {
+            if level < 0x20 && REPETITION_OFFSET as usize <= count {
+                // レベル反復表によらないランレングス圧縮(b)
+                let n = count.min(max_repetitions);
+                compressed.push(0xC0 | level);
+                compressed.push((n - REPETITION_OFFSET as usize) as u8);
+                count -= n;
+            } else if level < 0x40 {
+                // 頻度が多い単独のレベル値(c)
+                compressed.push(0x80 | level);
+                count -= 1;
+            } else {
+                // 頻度が少ない単独のレベル値(d)
+                compressed.push(0xFE);
+                compressed.push(level);
+                count -= 1;
+            }
+        }
+    }
+
+    compressed
+}